@@ -0,0 +1,68 @@
+// Written by Alexander Stocko <as@coder.gg>
+//
+// To the extent possible under law, the author has dedicated all copyright
+// and related and neighboring rights to this software to the public domain
+// worldwide. This software is distributed without any warranty.
+//
+// See <LICENSE or http://creativecommons.org/publicdomain/zero/1.0/>
+
+//! Compares the bulk fill functions against filling one value at a time
+//! through the `Rng` trait. Run with `cargo bench --bench fill`.
+
+extern crate xorshift;
+
+use std::hint::black_box;
+use std::time::Instant;
+
+use xorshift::{Rng, Xoroshiro128, Xoroshiro128x4, Xoroshiro128x8, Xorshift128,
+               Xorshift128x4, Xorshift128x8, Xorshift1024, Xorshift1024Block};
+
+const LEN: usize = 1 << 16;
+const ROUNDS: usize = 2000;
+
+fn per_call<R: Rng>(rng: &mut R, dest: &mut [u64]) {
+    for x in dest.iter_mut() {
+        *x = rng.next_u64();
+    }
+}
+
+fn report(name: &str, path: &str, start: Instant) {
+    let secs = start.elapsed().as_secs_f64();
+    let bytes = (LEN * ROUNDS * 8) as f64;
+    println!("{:<18} {:<10} {:>8.2} GB/s", name, path, bytes / secs / 1e9);
+}
+
+macro_rules! bench {
+    ($name:expr, $rng:expr) => {{
+        let mut buf = vec![0u64; LEN];
+
+        let mut rng = $rng;
+        let start = Instant::now();
+        for _ in 0..ROUNDS {
+            per_call(&mut rng, black_box(&mut buf));
+        }
+        report($name, "per-call", start);
+
+        let mut rng = $rng;
+        let start = Instant::now();
+        for _ in 0..ROUNDS {
+            rng.fill_u64(black_box(&mut buf));
+        }
+        report($name, "fill_u64", start);
+    }}
+}
+
+fn main() {
+    let seed = 1477780064718404645;
+    bench!("Xorshift1024", Xorshift1024::from_seed_conditioned(&[seed]));
+    bench!("Xorshift1024Block",
+           Xorshift1024Block::new(Xorshift1024::from_seed_conditioned(&[seed])));
+
+    let base = Xoroshiro128::from_seed_conditioned(&[seed]);
+    bench!("Xoroshiro128x4", Xoroshiro128x4::new(base));
+    bench!("Xoroshiro128x8", Xoroshiro128x8::new(base));
+
+    let base = Xorshift128::from_seed_conditioned(&[seed]);
+    bench!("Xorshift128x4", Xorshift128x4::new(base));
+    bench!("Xorshift128x8", Xorshift128x8::new(base));
+}
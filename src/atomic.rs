@@ -0,0 +1,200 @@
+// Written by Alexander Stocko <as@coder.gg>
+//
+// To the extent possible under law, the author has dedicated all copyright
+// and related and neighboring rights to this software to the public domain
+// worldwide. This software is distributed without any warranty.
+//
+// See <LICENSE or http://creativecommons.org/publicdomain/zero/1.0/>
+
+//! The lock-free `AtomicSplitMix64` random number generator.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+
+#[cfg(feature = "std")]
+use rand::Rng;
+
+use float;
+use hash::mix64;
+use splitmix64::SplitMix64;
+
+const GAMMA: u64 = 0x9E3779B97F4A7C15;
+
+/// A splitmix64 generator that can be shared between threads without a
+/// lock.
+///
+/// The splitmix64 state update is a plain addition, so each output claims
+/// its counter value with a single `fetch_add` and then applies the
+/// finalizer. Outputs are exactly those of `SplitMix64::new(seed)`; when
+/// several threads draw concurrently each receives a distinct subset, in
+/// an order that depends on scheduling.
+///
+/// ```rust
+/// use xorshift::AtomicSplitMix64;
+///
+/// static RNG: AtomicSplitMix64 = AtomicSplitMix64::new(42);
+///
+/// let x = RNG.next_u64();
+/// let y = RNG.next_u64();
+/// assert!(x != y);
+/// ```
+#[derive(Debug)]
+pub struct AtomicSplitMix64(AtomicU64);
+
+impl AtomicSplitMix64 {
+    /// Create a generator from a 64-bit seed.
+    pub const fn new(seed: u64) -> AtomicSplitMix64 {
+        AtomicSplitMix64(AtomicU64::new(seed))
+    }
+
+    /// Return the next random `u64`.
+    #[inline]
+    pub fn next_u64(&self) -> u64 {
+        mix64(self.0.fetch_add(GAMMA, Ordering::Relaxed).wrapping_add(GAMMA))
+    }
+
+    /// Return the next random `u32`, taken from the upper half of the next
+    /// `u64`.
+    #[inline]
+    pub fn next_u32(&self) -> u32 {
+        (self.next_u64() >> 32) as u32
+    }
+
+    /// Return the next random `f64` in [0, 1), built from the upper 53 bits
+    /// of the next `u64`.
+    #[inline]
+    pub fn next_f64(&self) -> f64 {
+        float::f64_from_u64(self.next_u64())
+    }
+
+    /// Return the next random `f32` in [0, 1), built from the upper 24 bits
+    /// of the next `u64`.
+    #[inline]
+    pub fn next_f32(&self) -> f32 {
+        float::f32_from_u64(self.next_u64())
+    }
+
+    /// Fill `dest` with random `u64`s.
+    ///
+    /// The whole block is claimed with one `fetch_add`, so `dest` receives
+    /// consecutive outputs of the sequential stream even under contention.
+    pub fn fill_u64(&self, dest: &mut [u64]) {
+        let count = (dest.len() as u64).wrapping_mul(GAMMA);
+        let mut rng = SplitMix64::new(self.0.fetch_add(count, Ordering::Relaxed));
+        for x in dest {
+            *x = rng.next_u64();
+        }
+    }
+
+    /// Return a sequential generator that continues from the current state.
+    pub fn to_splitmix64(&self) -> SplitMix64 {
+        SplitMix64::new(self.0.load(Ordering::Relaxed))
+    }
+}
+
+impl From<SplitMix64> for AtomicSplitMix64 {
+    /// Create a shared generator that continues the sequential one.
+    fn from(rng: SplitMix64) -> AtomicSplitMix64 {
+        AtomicSplitMix64::new(rng.state())
+    }
+}
+
+#[cfg(feature = "std")]
+impl Rng for AtomicSplitMix64 {
+    #[inline]
+    fn next_u32(&mut self) -> u32 {
+        AtomicSplitMix64::next_u32(self)
+    }
+
+    #[inline]
+    fn next_u64(&mut self) -> u64 {
+        AtomicSplitMix64::next_u64(self)
+    }
+
+    #[inline]
+    fn next_f32(&mut self) -> f32 {
+        AtomicSplitMix64::next_f32(self)
+    }
+
+    #[inline]
+    fn next_f64(&mut self) -> f64 {
+        AtomicSplitMix64::next_f64(self)
+    }
+}
+
+/// Shared references are generators too, so a `static` can be passed
+/// wherever an `Rng` is expected.
+#[cfg(feature = "std")]
+impl Rng for &AtomicSplitMix64 {
+    #[inline]
+    fn next_u32(&mut self) -> u32 {
+        AtomicSplitMix64::next_u32(self)
+    }
+
+    #[inline]
+    fn next_u64(&mut self) -> u64 {
+        AtomicSplitMix64::next_u64(self)
+    }
+
+    #[inline]
+    fn next_f32(&mut self) -> f32 {
+        AtomicSplitMix64::next_f32(self)
+    }
+
+    #[inline]
+    fn next_f64(&mut self) -> f64 {
+        AtomicSplitMix64::next_f64(self)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+    use std::sync::Arc;
+    use std::thread;
+    use rand::Rng;
+    use splitmix64::SplitMix64;
+    use super::AtomicSplitMix64;
+    #[test]
+    fn test() {
+        // Single-threaded use matches the sequential generator
+        let seed: u64 = 1477776061723855037;
+        let shared = AtomicSplitMix64::new(seed);
+        let mut rng = SplitMix64::new(seed);
+        for _ in 0..100 {
+            assert_eq!(shared.next_u64(), rng.next_u64());
+        }
+
+        let mut block = [0; 10];
+        shared.fill_u64(&mut block);
+        for x in block.iter() {
+            assert_eq!(*x, rng.next_u64());
+        }
+
+        assert_eq!(shared.to_splitmix64().next_u64(), rng.next_u64());
+        let mut from = AtomicSplitMix64::from(rng);
+        assert_eq!(Rng::next_u64(&mut from), rng.next_u64());
+        let mut shared_ref = &from;
+        assert_eq!(Rng::next_u64(&mut shared_ref), rng.next_u64());
+    }
+
+    #[test]
+    fn test_threads() {
+        // Concurrent draws are the sequential outputs, each handed out once
+        let seed: u64 = 1477776061723855037;
+        let shared = Arc::new(AtomicSplitMix64::new(seed));
+        let handles = (0..8).map(|_| {
+            let shared = shared.clone();
+            thread::spawn(move || (0..10000).map(|_| shared.next_u64()).collect::<Vec<u64>>())
+        }).collect::<Vec<_>>();
+
+        let mut seen = HashSet::new();
+        for handle in handles {
+            for x in handle.join().unwrap() {
+                assert!(seen.insert(x));
+            }
+        }
+        let mut rng = SplitMix64::new(seed);
+        assert!((0..80000).all(|_| seen.contains(&rng.next_u64())));
+    }
+}
@@ -0,0 +1,130 @@
+// Written by Alexander Stocko <as@coder.gg>
+//
+// To the extent possible under law, the author has dedicated all copyright
+// and related and neighboring rights to this software to the public domain
+// worldwide. This software is distributed without any warranty.
+//
+// See <LICENSE or http://creativecommons.org/publicdomain/zero/1.0/>
+
+//! Extraction of single bits and small bit fields.
+
+use rand::Rng;
+
+use RngInfo;
+
+#[inline]
+fn shl(x: u64, k: u32) -> u64 {
+    x.checked_shl(k).unwrap_or(0)
+}
+
+/// Hands out random bits one at a time, or in chunks of up to 64.
+///
+/// # Description
+/// Each `u64` drawn from the generator is buffered and consumed from the
+/// most significant bit down. The lowest `RngInfo::WEAK_LOW_BITS` bits of
+/// every word, which are LFSRs for the `+` and `*` scramblers, are never
+/// used.
+///
+/// # Examples
+/// ```rust
+/// use xorshift::{BitStream, Xoroshiro128};
+///
+/// let mut bits = BitStream::new(Xoroshiro128::from_seed_conditioned(&[42]));
+/// let heads = bits.next_bool();
+/// let die = bits.next_bits(3);
+/// assert!(die < 8);
+/// ```
+#[derive(Copy, Clone, Debug)]
+pub struct BitStream<R> {
+    rng: R,
+    buf: u64,
+    avail: u32,
+}
+
+impl<R: Rng + RngInfo> BitStream<R> {
+    /// Wrap a generator.
+    pub fn new(rng: R) -> BitStream<R> {
+        BitStream {
+            rng,
+            buf: 0,
+            avail: 0,
+        }
+    }
+
+    /// Unwrap the generator, discarding any buffered bits.
+    pub fn into_inner(self) -> R {
+        self.rng
+    }
+
+    #[inline]
+    fn refill(&mut self) {
+        self.buf = self.rng.next_u64();
+        self.avail = 64 - R::WEAK_LOW_BITS;
+    }
+
+    /// Return the next random bit as a `bool`.
+    #[inline]
+    pub fn next_bool(&mut self) -> bool {
+        if self.avail == 0 {
+            self.refill();
+        }
+        let bit = self.buf >> 63;
+        self.buf <<= 1;
+        self.avail -= 1;
+        bit == 1
+    }
+
+    /// Return the next `n` random bits in the low bits of a `u64`.
+    ///
+    /// Bits are returned in the order they were drawn, with earlier bits
+    /// more significant. Panics if `n` is greater than 64.
+    #[inline]
+    pub fn next_bits(&mut self, n: u32) -> u64 {
+        assert!(n <= 64, "BitStream can return at most 64 bits at once.");
+
+        let mut result = 0;
+        let mut n = n;
+        while n > 0 {
+            if self.avail == 0 {
+                self.refill();
+            }
+            let take = n.min(self.avail);
+            result = shl(result, take) | self.buf >> (64 - take);
+            self.buf = shl(self.buf, take);
+            self.avail -= take;
+            n -= take;
+        }
+        result
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use rand::Rng;
+    use ext::RngExt;
+    use xorshift1024::Xorshift1024;
+    use super::BitStream;
+    #[test]
+    fn test() {
+        let mut rng = Xorshift1024::from_seed_conditioned(&[1477777179826044140]);
+        let mut bits = BitStream::new(rng);
+        let words = rng.gen_iter::<u64>().take(3).collect::<Vec<u64>>();
+
+        // The top 61 bits of each word, the lowest three being LFSRs
+        for b in 0..61 {
+            assert_eq!(bits.next_bool(), (words[0] >> (63 - b)) & 1 == 1);
+        }
+
+        // A 64-bit chunk spans two words
+        let expected = (words[1] >> 3) << 3 | words[2] >> 61;
+        assert_eq!(bits.next_bits(64), expected);
+        assert_eq!(bits.next_bits(0), 0);
+        assert_eq!(bits.next_bits(5), (words[2] >> 56) & 0x1f);
+
+        let mut rng = Xorshift1024::from_seed_conditioned(&[1477777179826044140]);
+        for x in words {
+            assert_eq!(rng.gen_bool_sign(), x >> 63 == 1);
+        }
+    }
+}
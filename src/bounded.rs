@@ -0,0 +1,23 @@
+// Written by Alexander Stocko <as@coder.gg>
+//
+// To the extent possible under law, the author has dedicated all copyright
+// and related and neighboring rights to this software to the public domain
+// worldwide. This software is distributed without any warranty.
+//
+// See <LICENSE or http://creativecommons.org/publicdomain/zero/1.0/>
+
+//! Lemire's bounded integer method, shared by `uniform` and `keyed`.
+
+/// Map `first` to [0, n) with Lemire's method, calling `next` for a new
+/// word whenever the current one is rejected. `n` must not be zero.
+#[inline(always)]
+pub fn below<F: FnMut() -> u64>(first: u64, n: u64, mut next: F) -> u64 {
+    let mut m = first as u128 * n as u128;
+    if (m as u64) < n {
+        let threshold = n.wrapping_neg() % n;
+        while (m as u64) < threshold {
+            m = next() as u128 * n as u128;
+        }
+    }
+    (m >> 64) as u64
+}
@@ -0,0 +1,212 @@
+// Written by Alexander Stocko <as@coder.gg>
+//
+// To the extent possible under law, the author has dedicated all copyright
+// and related and neighboring rights to this software to the public domain
+// worldwide. This software is distributed without any warranty.
+//
+// See <LICENSE or http://creativecommons.org/publicdomain/zero/1.0/>
+
+#[cfg(feature = "serde")]
+use std::convert::TryFrom;
+
+use rand::Rng;
+use super::Distribution;
+
+/// A weighted discrete distribution over `0..n`, sampled in constant time
+/// with Walker's alias method, built using Vose's algorithm [1].
+///
+/// Each draw takes a single `next_u64`, which is multiplied by `n`. The
+/// high word of the product selects the column and the low word, the
+/// remaining fraction, is compared with the column's threshold to decide
+/// between the column and its alias. The column choice is biased by at
+/// most `n / 2^64`.
+///
+/// With the `serde` feature the table can be serialized, so large tables
+/// only need to be built once. Deserialization checks that the table is
+/// well formed.
+///
+/// [1]: Michael D. Vose, [*A Linear Algorithm for Generating Random
+/// Numbers with a Given Distribution*](https://doi.org/10.1109/32.92917)
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "RawAliasTable"))]
+pub struct AliasTable {
+    threshold: Vec<u64>,
+    alias: Vec<u32>,
+}
+
+// A deserialized table before it has been checked.
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+struct RawAliasTable {
+    threshold: Vec<u64>,
+    alias: Vec<u32>,
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<RawAliasTable> for AliasTable {
+    type Error = &'static str;
+
+    fn try_from(raw: RawAliasTable) -> Result<AliasTable, &'static str> {
+        let n = raw.alias.len();
+        if n == 0 {
+            return Err("alias table has no categories");
+        }
+        if raw.threshold.len() != n {
+            return Err("alias table has mismatched threshold and alias lengths");
+        }
+        if n as u64 > u32::MAX as u64 {
+            return Err("alias table has too many categories");
+        }
+        if raw.alias.iter().any(|a| *a as usize >= n) {
+            return Err("alias table has an alias out of range");
+        }
+        Ok(AliasTable {
+            threshold: raw.threshold,
+            alias: raw.alias,
+        })
+    }
+}
+
+impl AliasTable {
+    /// Build a table where index `i` is drawn with probability proportional
+    /// to `weights[i]`.
+    ///
+    /// Panics if `weights` is empty or has more than `u32::MAX` entries, or
+    /// if any weight is negative or not finite, or all weights are zero.
+    pub fn new(weights: &[f64]) -> AliasTable {
+        let n = weights.len();
+        assert!(n != 0, "AliasTable::new called with no weights.");
+        assert!(n as u64 <= u32::MAX as u64, "AliasTable::new called with too many weights.");
+        assert!(weights.iter().all(|w| *w >= 0.0 && w.is_finite()),
+                "AliasTable::new called with a negative or non-finite weight.");
+        let sum = weights.iter().sum::<f64>();
+        assert!(sum > 0.0 && sum.is_finite(), "AliasTable::new called with zero total weight.");
+
+        let scale = n as f64 / sum;
+        let mut prob = weights.iter().map(|w| w * scale).collect::<Vec<f64>>();
+        let mut threshold = vec![u64::MAX; n];
+        let mut alias = (0..n as u32).collect::<Vec<u32>>();
+
+        let (mut small, mut large): (Vec<u32>, Vec<u32>) = (0..n as u32)
+            .partition(|i| prob[*i as usize] < 1.0);
+
+        while let (Some(&s), Some(&l)) = (small.last(), large.last()) {
+            small.pop();
+            threshold[s as usize] = to_threshold(prob[s as usize]);
+            alias[s as usize] = l;
+
+            prob[l as usize] -= 1.0 - prob[s as usize];
+            if prob[l as usize] < 1.0 {
+                large.pop();
+                small.push(l);
+            }
+        }
+
+        // Whatever is left over is 1 up to rounding error. These columns
+        // alias themselves, so their threshold does not matter.
+        AliasTable {
+            threshold,
+            alias,
+        }
+    }
+
+    /// Number of categories.
+    pub fn len(&self) -> usize {
+        self.alias.len()
+    }
+
+    /// Always false, as a table has at least one category.
+    pub fn is_empty(&self) -> bool {
+        self.alias.is_empty()
+    }
+}
+
+// Probability in [0, 1) as a 64-bit fixed-point fraction.
+#[inline]
+fn to_threshold(p: f64) -> u64 {
+    (p * (1u128 << 64) as f64) as u64
+}
+
+impl Distribution<usize> for AliasTable {
+    #[inline]
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> usize {
+        let m = rng.next_u64() as u128 * self.threshold.len() as u128;
+        let column = (m >> 64) as usize;
+        if (m as u64) < self.threshold[column] {
+            column
+        } else {
+            self.alias[column] as usize
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use distributions::Distribution;
+    use distributions::tests::assert_pinned;
+    use test::{pinned, xoroshiro, xorshift1024};
+    use super::AliasTable;
+
+    // Pearson's chi-square statistic of `draws` samples against `weights`.
+    fn chi_square<F: FnMut() -> usize>(weights: &[f64], draws: usize, mut sample: F) -> f64 {
+        let sum = weights.iter().sum::<f64>();
+        let mut counts = vec![0usize; weights.len()];
+        for _ in 0..draws {
+            counts[sample()] += 1;
+        }
+        counts.iter().zip(weights).map(|(c, w)| {
+            let expected = w / sum * draws as f64;
+            (*c as f64 - expected) * (*c as f64 - expected) / expected
+        }).sum()
+    }
+
+    #[test]
+    fn test() {
+        let table = AliasTable::new(&[1.0, 2.0, 0.0, 4.0, 3.0]);
+        let mut rng = pinned();
+        assert_pinned(&table, &mut rng,
+                      &[3, 3, 3, 4, 4, 3, 4, 3, 4, 3, 3, 4, 3, 4, 1, 4, 1, 1, 4, 1]);
+        assert_eq!(table.len(), 5);
+
+        let table = AliasTable::new(&[0.0, 7.5, 0.0]);
+        assert!((0..1000).all(|_| table.sample(&mut rng) == 1));
+    }
+
+    #[test]
+    fn test_chi_square() {
+        // Critical values for p = 0.001 with 9 and 999 degrees of freedom
+        let weights = (1..11).map(|w| w as f64).collect::<Vec<f64>>();
+        let table = AliasTable::new(&weights);
+        let mut rng = xoroshiro();
+        assert!(chi_square(&weights, 100000, || table.sample(&mut rng)) < 27.877);
+
+        let weights = (0..1000).map(|i| 1.0 + (i % 7) as f64).collect::<Vec<f64>>();
+        let table = AliasTable::new(&weights);
+        let mut rng = xorshift1024();
+        assert!(chi_square(&weights, 1000000, || table.sample(&mut rng)) < 1142.0);
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn test_serde() {
+        extern crate serde_json;
+
+        let table = AliasTable::new(&[1.0, 2.0, 0.0, 4.0, 3.0]);
+        let json = serde_json::to_string(&table).unwrap();
+        assert_eq!(serde_json::from_str::<AliasTable>(&json).unwrap(), table);
+
+        for bad in [r#"{"threshold":[],"alias":[]}"#,
+                    r#"{"threshold":[1,2],"alias":[0]}"#,
+                    r#"{"threshold":[1,2],"alias":[0,2]}"#].iter() {
+            assert!(serde_json::from_str::<AliasTable>(bad).is_err());
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_zero_weights() {
+        AliasTable::new(&[0.0, 0.0]);
+    }
+}
@@ -0,0 +1,199 @@
+// Written by Alexander Stocko <as@coder.gg>
+//
+// To the extent possible under law, the author has dedicated all copyright
+// and related and neighboring rights to this software to the public domain
+// worldwide. This software is distributed without any warranty.
+//
+// See <LICENSE or http://creativecommons.org/publicdomain/zero/1.0/>
+
+use rand::Rng;
+use float::f64_from_u64;
+use super::Distribution;
+
+/// The binomial distribution: the number of successes in `n` independent
+/// trials with success probability `p`.
+///
+/// Sampled from `min(p, 1 - p)` and reflected. When the mean is above 30
+/// the BTPE algorithm of Kachitvichyanukul and Schmeiser [1] is used;
+/// otherwise the distribution is inverted sequentially.
+///
+/// [1]: Voratas Kachitvichyanukul and Bruce W. Schmeiser, [*Binomial Random
+/// Variate Generation*](https://doi.org/10.1145/42372.42381)
+#[derive(Clone, Copy, Debug)]
+pub struct Binomial {
+    n: u64,
+    p: f64,
+}
+
+impl Binomial {
+    /// Create a binomial distribution.
+    ///
+    /// Panics if `p` is not in [0, 1].
+    pub fn new(n: u64, p: f64) -> Binomial {
+        assert!((0.0..=1.0).contains(&p), "Binomial::new called with p outside [0, 1].");
+        Binomial { n, p }
+    }
+}
+
+impl Distribution<u64> for Binomial {
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> u64 {
+        let p = self.p.min(1.0 - self.p);
+        let k = if p == 0.0 || self.n == 0 {
+            0
+        } else if self.n as f64 * p <= 30.0 {
+            inversion(rng, self.n, p)
+        } else {
+            btpe(rng, self.n, p)
+        };
+
+        if self.p > 0.5 { self.n - k } else { k }
+    }
+}
+
+fn inversion<R: Rng + ?Sized>(rng: &mut R, n: u64, p: f64) -> u64 {
+    let q = 1.0 - p;
+    let n_f = n as f64;
+    let q_n = (n_f * q.ln()).exp();
+    let np = n_f * p;
+    let bound = n_f.min(np + 10.0 * (np * q + 1.0).sqrt());
+
+    let mut k = 0;
+    let mut px = q_n;
+    let mut u = f64_from_u64(rng.next_u64());
+    while u > px {
+        k += 1;
+        if k as f64 > bound {
+            k = 0;
+            px = q_n;
+            u = f64_from_u64(rng.next_u64());
+        } else {
+            u -= px;
+            px = ((n - k + 1) as f64 * p * px) / (k as f64 * q);
+        }
+    }
+    k
+}
+
+#[allow(clippy::many_single_char_names)]
+fn btpe<R: Rng + ?Sized>(rng: &mut R, n: u64, p: f64) -> u64 {
+    let n = n as f64;
+    let (r, q) = (p, 1.0 - p);
+    let nrq = n * r * q;
+    let fm = n * r + r;
+    let m = fm.floor();
+    let p1 = (2.195 * nrq.sqrt() - 4.6 * q).floor() + 0.5;
+    let xm = m + 0.5;
+    let xl = xm - p1;
+    let xr = xm + p1;
+    let c = 0.134 + 20.5 / (15.3 + m);
+    let a = (fm - xl) / (fm - xl * r);
+    let lambda_l = a * (1.0 + a / 2.0);
+    let a = (xr - fm) / (xr * q);
+    let lambda_r = a * (1.0 + a / 2.0);
+    let p2 = p1 * (1.0 + 2.0 * c);
+    let p3 = p2 + c / lambda_l;
+    let p4 = p3 + c / lambda_r;
+
+    loop {
+        let u = f64_from_u64(rng.next_u64()) * p4;
+        let mut v = f64_from_u64(rng.next_u64());
+
+        // Triangular region, accepted immediately
+        if u <= p1 {
+            return (xm - p1 * v + u).floor() as u64;
+        }
+
+        // Parallelograms and exponential tails
+        let y;
+        if u <= p2 {
+            let x = xl + (u - p1) / c;
+            v = v * c + 1.0 - (m - x + 0.5).abs() / p1;
+            if v > 1.0 {
+                continue;
+            }
+            y = x.floor();
+        } else if u <= p3 {
+            y = (xl + v.ln() / lambda_l).floor();
+            if y < 0.0 || v == 0.0 {
+                continue;
+            }
+            v *= (u - p2) * lambda_l;
+        } else {
+            y = (xr - v.ln() / lambda_r).floor();
+            if y > n || v == 0.0 {
+                continue;
+            }
+            v *= (u - p3) * lambda_r;
+        }
+
+        // Explicit evaluation of the density ratio when y is close to m
+        let k = (y - m).abs();
+        if k <= 20.0 || k >= nrq / 2.0 - 1.0 {
+            let s = r / q;
+            let a = s * (n + 1.0);
+            let mut f = 1.0;
+            let mut i = m.min(y) + 1.0;
+            while i <= m.max(y) {
+                if m < y { f *= a / i - s } else { f /= a / i - s }
+                i += 1.0;
+            }
+            if v > f {
+                continue;
+            }
+            return y as u64;
+        }
+
+        // Squeeze using upper and lower bounds on log(f(y))
+        let rho = (k / nrq) * ((k * (k / 3.0 + 0.625) + 0.16666666666666666) / nrq + 0.5);
+        let t = -k * k / (2.0 * nrq);
+        let log_v = v.ln();
+        if log_v < t - rho {
+            return y as u64;
+        }
+        if log_v > t + rho {
+            continue;
+        }
+
+        // Final acceptance test with Stirling's formula
+        let x1 = y + 1.0;
+        let f1 = m + 1.0;
+        let z = n + 1.0 - m;
+        let w = n - y + 1.0;
+        let stirling = |x: f64| {
+            let x2 = x * x;
+            (13680. - (462. - (132. - (99. - 140. / x2) / x2) / x2) / x2) / x / 166320.
+        };
+        if log_v <= xm * (f1 / x1).ln() + (n - m + 0.5) * (z / w).ln() +
+                    (y - m) * (w * r / (x1 * q)).ln() +
+                    stirling(f1) + stirling(z) + stirling(x1) + stirling(w) {
+            return y as u64;
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use distributions::Distribution;
+    use distributions::tests::{assert_pinned, moments, AsF64};
+    use test::{pinned, xoroshiro, xorshift1024};
+    use super::Binomial;
+    #[test]
+    fn test() {
+        let mut rng = pinned();
+        assert_pinned(&Binomial::new(20, 0.3), &mut rng,
+                      &[4, 4, 7, 6, 8, 9, 9, 7, 6, 9]);
+        assert_pinned(&Binomial::new(1000, 0.7), &mut rng,
+                      &[707, 686, 699, 696, 690, 716, 692, 698, 686, 692]);
+        assert_eq!(Binomial::new(10, 1.0).sample(&mut rng), 10);
+        assert_eq!(Binomial::new(0, 0.5).sample(&mut rng), 0);
+
+        let dist = Binomial::new(20, 0.3);
+        let (mean, var) = moments(&AsF64(dist), &mut xoroshiro(), 100000);
+        assert!((mean - 6.0).abs() < 0.03 && (var - 4.2).abs() < 0.1);
+
+        let dist = Binomial::new(1000, 0.7);
+        let (mean, var) = moments(&AsF64(dist), &mut xorshift1024(), 100000);
+        assert!((mean - 700.0).abs() < 0.2 && (var - 210.0).abs() < 4.0);
+    }
+}
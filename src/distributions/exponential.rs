@@ -0,0 +1,67 @@
+// Written by Alexander Stocko <as@coder.gg>
+//
+// To the extent possible under law, the author has dedicated all copyright
+// and related and neighboring rights to this software to the public domain
+// worldwide. This software is distributed without any warranty.
+//
+// See <LICENSE or http://creativecommons.org/publicdomain/zero/1.0/>
+
+use rand::Rng;
+use float::f64_open_from_u64;
+use super::{ziggurat, Distribution};
+use super::ziggurat_tables::{ZIG_EXP_F, ZIG_EXP_R, ZIG_EXP_X};
+
+/// The exponential distribution with rate 1.
+///
+/// Sampled with the ziggurat method; about 99% of draws take a single
+/// `next_u64`. Divide the result by `lambda` for other rates.
+#[derive(Clone, Copy, Debug)]
+pub struct Exp1;
+
+impl Distribution<f64> for Exp1 {
+    #[inline]
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> f64 {
+        #[inline]
+        fn pdf(x: f64) -> f64 {
+            (-x).exp()
+        }
+
+        // The tail beyond ZIG_EXP_R is itself exponential.
+        #[inline]
+        fn zero_case<R: Rng + ?Sized>(rng: &mut R, _u: f64) -> f64 {
+            ZIG_EXP_R - f64_open_from_u64(rng.next_u64()).ln()
+        }
+
+        ziggurat(rng, false, &ZIG_EXP_X, &ZIG_EXP_F, pdf, zero_case)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use distributions::Distribution;
+    use distributions::tests::{assert_pinned_close, moments, Words};
+    use test::{pinned, xoroshiro, xorshift1024};
+    use super::Exp1;
+    #[test]
+    fn test() {
+        assert_pinned_close(&Exp1, &mut pinned(),
+                            &[0.3879361912084622, 2.2728039078639837, 0.017487241399813887,
+                              1.084929338180875, 0.20211222007729066, 0.18504746549483528]);
+
+        let (mean, var) = moments(&Exp1, &mut xoroshiro(), 100000);
+        assert!((mean - 1.0).abs() < 0.01 && (var - 1.0).abs() < 0.03);
+
+        let (mean, var) = moments(&Exp1, &mut xorshift1024(), 100000);
+        assert!((mean - 1.0).abs() < 0.01 && (var - 1.0).abs() < 0.03);
+    }
+
+    #[test]
+    fn test_tail() {
+        // Layer 0 with the largest fraction falls through to the tail
+        let words = [0x00ff_ffff_ffff_fff8u64, 1 << 63];
+        let mut rng = Words(words.iter().cloned());
+        let x = Exp1.sample(&mut rng);
+        assert_eq!(x, super::ZIG_EXP_R - (0.5f64 + 2f64.powi(-53)).ln());
+    }
+}
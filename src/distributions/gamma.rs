@@ -0,0 +1,177 @@
+// Written by Alexander Stocko <as@coder.gg>
+//
+// To the extent possible under law, the author has dedicated all copyright
+// and related and neighboring rights to this software to the public domain
+// worldwide. This software is distributed without any warranty.
+//
+// See <LICENSE or http://creativecommons.org/publicdomain/zero/1.0/>
+
+use rand::Rng;
+use float::{f64_from_u64, f64_open_from_u64};
+use super::{Distribution, StandardNormal};
+
+/// The gamma distribution with the given shape and scale.
+///
+/// Sampled with the method of Marsaglia and Tsang [1]. Shapes below 1 are
+/// boosted from `shape + 1` with one extra uniform draw.
+///
+/// [1]: George Marsaglia and Wai Wan Tsang, [*A Simple Method for
+/// Generating Gamma Variables*](https://doi.org/10.1145/358407.358414)
+#[derive(Clone, Copy, Debug)]
+pub struct Gamma {
+    shape: f64,
+    scale: f64,
+    d: f64,
+    c: f64,
+}
+
+impl Gamma {
+    /// Create a gamma distribution.
+    ///
+    /// Panics if `shape` or `scale` is not positive and finite.
+    pub fn new(shape: f64, scale: f64) -> Gamma {
+        assert!(shape > 0.0 && shape.is_finite(), "Gamma::new called with shape <= 0.");
+        assert!(scale > 0.0 && scale.is_finite(), "Gamma::new called with scale <= 0.");
+
+        let d = if shape < 1.0 { shape + 1.0 } else { shape } - 1.0 / 3.0;
+        Gamma {
+            shape,
+            scale,
+            d,
+            c: 1.0 / (9.0 * d).sqrt(),
+        }
+    }
+}
+
+impl Distribution<f64> for Gamma {
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> f64 {
+        let x = loop {
+            let (x, v) = loop {
+                let x = StandardNormal.sample(rng);
+                let v = 1.0 + self.c * x;
+                if v > 0.0 {
+                    break (x, v * v * v);
+                }
+            };
+
+            let u = f64_open_from_u64(rng.next_u64());
+            let x_sq = x * x;
+            if u < 1.0 - 0.0331 * x_sq * x_sq ||
+               u.ln() < 0.5 * x_sq + self.d * (1.0 - v + v.ln()) {
+                break self.d * v;
+            }
+        };
+
+        if self.shape < 1.0 {
+            x * f64_open_from_u64(rng.next_u64()).powf(1.0 / self.shape) * self.scale
+        } else {
+            x * self.scale
+        }
+    }
+}
+
+/// The beta distribution with shape parameters `a` and `b`.
+///
+/// Uses Jöhnk's algorithm when both parameters are at most 1, and the
+/// ratio of two gamma variates otherwise.
+#[derive(Clone, Copy, Debug)]
+pub struct Beta {
+    a: f64,
+    b: f64,
+    gamma_a: Gamma,
+    gamma_b: Gamma,
+}
+
+impl Beta {
+    /// Create a beta distribution.
+    ///
+    /// Panics if `a` or `b` is not positive and finite.
+    pub fn new(a: f64, b: f64) -> Beta {
+        assert!(a > 0.0 && a.is_finite(), "Beta::new called with a <= 0.");
+        assert!(b > 0.0 && b.is_finite(), "Beta::new called with b <= 0.");
+
+        Beta {
+            a,
+            b,
+            gamma_a: Gamma::new(a, 1.0),
+            gamma_b: Gamma::new(b, 1.0),
+        }
+    }
+}
+
+impl Distribution<f64> for Beta {
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> f64 {
+        if self.a > 1.0 || self.b > 1.0 {
+            let x = self.gamma_a.sample(rng);
+            let y = self.gamma_b.sample(rng);
+            return x / (x + y);
+        }
+
+        loop {
+            let u = f64_from_u64(rng.next_u64());
+            let v = f64_from_u64(rng.next_u64());
+            let x = u.powf(1.0 / self.a);
+            let y = v.powf(1.0 / self.b);
+            let sum = x + y;
+            if sum <= 1.0 && u + v > 0.0 {
+                if sum > 0.0 {
+                    return x / sum;
+                }
+
+                // Both powers underflowed, so take the ratio in log space
+                let log_x = u.ln() / self.a;
+                let log_y = v.ln() / self.b;
+                let log_m = log_x.max(log_y);
+                let (log_x, log_y) = (log_x - log_m, log_y - log_m);
+                return (log_x - (log_x.exp() + log_y.exp()).ln()).exp();
+            }
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use distributions::tests::{assert_pinned_close, moments};
+    use test::{pinned, xoroshiro, xorshift1024};
+    use super::{Beta, Gamma};
+    #[test]
+    fn test_gamma() {
+        let mut rng = pinned();
+        assert_pinned_close(&Gamma::new(2.5, 2.0), &mut rng,
+                            &[3.635353907758283, 4.275462838116413, 5.128976439861023,
+                              6.796075907930083]);
+        assert_pinned_close(&Gamma::new(0.5, 1.0), &mut rng,
+                            &[0.10388437469292142, 0.4578935466018445, 0.14992725607720328,
+                              0.004180805684860942]);
+
+        let (mean, var) = moments(&Gamma::new(2.5, 2.0), &mut xoroshiro(), 100000);
+        assert!((mean - 5.0).abs() < 0.05 && (var - 10.0).abs() < 0.3);
+
+        let (mean, var) = moments(&Gamma::new(0.5, 1.0), &mut xorshift1024(), 100000);
+        assert!((mean - 0.5).abs() < 0.01 && (var - 0.5).abs() < 0.03);
+    }
+
+    #[test]
+    fn test_beta() {
+        let mut rng = pinned();
+        assert_pinned_close(&Beta::new(2.0, 5.0), &mut rng,
+                            &[0.22766251175831662, 0.2401276497156569, 0.3234386148553394,
+                              0.2673517520710777]);
+        assert_pinned_close(&Beta::new(0.5, 0.5), &mut rng,
+                            &[0.5411196654885807, 0.7062600954584382, 0.5884813928233616,
+                              0.47118593548055127]);
+
+        let (mean, var) = moments(&Beta::new(2.0, 5.0), &mut xoroshiro(), 100000);
+        assert!((mean - 2.0 / 7.0).abs() < 0.005 && (var - 10.0 / 392.0).abs() < 0.002);
+
+        let (mean, var) = moments(&Beta::new(0.5, 0.5), &mut xorshift1024(), 100000);
+        assert!((mean - 0.5).abs() < 0.005 && (var - 0.125).abs() < 0.005);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_gamma_invalid() {
+        Gamma::new(0.0, 1.0);
+    }
+}
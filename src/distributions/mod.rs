@@ -0,0 +1,156 @@
+// Written by Alexander Stocko <as@coder.gg>
+//
+// To the extent possible under law, the author has dedicated all copyright
+// and related and neighboring rights to this software to the public domain
+// worldwide. This software is distributed without any warranty.
+//
+// See <LICENSE or http://creativecommons.org/publicdomain/zero/1.0/>
+
+//! Non-uniform distributions with version-stable outputs.
+//!
+//! Samplers only consume whole `u64`s from the generator and convert them
+//! with the crate's own routines, so a given seed produces the same values
+//! across releases. Changing the output of any sampler is treated as a
+//! breaking change, and the tests pin the first outputs of each sampler
+//! for a fixed seed.
+//!
+//! Samplers also call the platform's `exp` and `ln`, whose last bits can
+//! differ between math libraries. Values on different platforms therefore
+//! agree to within rounding, not bit for bit.
+//!
+//! ```rust
+//! use xorshift::{SeedableRng, Xoroshiro128};
+//! use xorshift::distributions::{Distribution, Exp1, StandardNormal};
+//!
+//! let mut rng: Xoroshiro128 = SeedableRng::from_seed(&[1, 2][..]);
+//! let x = StandardNormal.sample(&mut rng);
+//! let t = Exp1.sample(&mut rng);
+//! assert!(x.is_finite() && t >= 0.0);
+//! ```
+
+use rand::Rng;
+
+pub use self::alias::AliasTable;
+pub use self::binomial::Binomial;
+pub use self::exponential::Exp1;
+pub use self::gamma::{Beta, Gamma};
+pub use self::normal::StandardNormal;
+pub use self::poisson::Poisson;
+
+mod alias;
+mod binomial;
+mod exponential;
+mod gamma;
+mod normal;
+mod poisson;
+mod ziggurat_tables;
+
+/// A probability distribution that can be sampled.
+pub trait Distribution<T> {
+    /// Generate a random value from the distribution.
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> T;
+}
+
+// Ziggurat sampling over 256 layers, following Doornik [1].
+//
+// Each attempt uses a single `u64`: the top 8 bits select the layer, the
+// next bit is the sign for symmetric distributions, and the following 52
+// bits form the fraction. The lowest 3 bits, which are weak for the `+`
+// and `*` scramblers, are discarded.
+//
+// [1]: Jurgen A. Doornik, [*An Improved Ziggurat Method to Generate Normal
+// Random Samples*](https://www.doornik.com/research/ziggurat.pdf)
+#[inline(always)]
+fn ziggurat<R, P, Z>(rng: &mut R,
+                     symmetric: bool,
+                     x_tab: &[f64; 257],
+                     f_tab: &[f64; 257],
+                     pdf: P,
+                     zero_case: Z)
+                     -> f64
+    where R: Rng + ?Sized, P: Fn(f64) -> f64, Z: FnOnce(&mut R, f64) -> f64
+{
+    const SCALE: f64 = 1.0 / (1u64 << 52) as f64;
+    loop {
+        let bits = rng.next_u64();
+        let i = (bits >> 56) as usize;
+        let f = ((bits >> 3) & ((1 << 52) - 1)) as f64 * SCALE;
+        let u = if symmetric && bits & (1 << 55) != 0 { -f } else { f };
+
+        let x = u * x_tab[i];
+        if f * x_tab[i] < x_tab[i + 1] {
+            return x;
+        }
+        if i == 0 {
+            return zero_case(rng, u);
+        }
+        let y = ::float::f64_from_u64(rng.next_u64());
+        if f_tab[i + 1] + (f_tab[i] - f_tab[i + 1]) * y < pdf(x) {
+            return x;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fmt::Debug;
+    use rand::Rng;
+    use super::Distribution;
+
+    // Replays a fixed sequence of words.
+    pub struct Words<I>(pub I);
+
+    impl<I: Iterator<Item = u64>> Rng for Words<I> {
+        fn next_u32(&mut self) -> u32 {
+            (self.next_u64() >> 32) as u32
+        }
+
+        fn next_u64(&mut self) -> u64 {
+            self.0.next().unwrap()
+        }
+    }
+
+    // Sample mean and variance of `n` draws.
+    pub fn moments<D, R>(dist: &D, rng: &mut R, n: usize) -> (f64, f64)
+        where D: Distribution<f64>, R: Rng
+    {
+        let (mut sum, mut sum_sq) = (0.0, 0.0);
+        for _ in 0..n {
+            let x = dist.sample(rng);
+            sum += x;
+            sum_sq += x * x;
+        }
+        let mean = sum / n as f64;
+        (mean, sum_sq / n as f64 - mean * mean)
+    }
+
+    // Draw `expected.len()` values from `dist` and compare them exactly.
+    // Counts only change with the platform's `exp` and `ln` if an
+    // acceptance test lands within rounding of its bound.
+    pub fn assert_pinned<T, D, R>(dist: &D, rng: &mut R, expected: &[T])
+        where T: PartialEq + Debug, D: Distribution<T>, R: Rng
+    {
+        let vals = expected.iter().map(|_| dist.sample(rng)).collect::<Vec<T>>();
+        assert_eq!(vals, expected);
+    }
+
+    // As `assert_pinned`, but compares to within rounding, since the
+    // values go through the platform's `exp` and `ln`.
+    pub fn assert_pinned_close<D, R>(dist: &D, rng: &mut R, expected: &[f64])
+        where D: Distribution<f64>, R: Rng
+    {
+        for e in expected {
+            let a = dist.sample(rng);
+            assert!((a - e).abs() <= 1e-12 * e.abs(), "{} != {}", a, e);
+        }
+    }
+
+    // Samples a distribution over counts as `f64`, for `moments`.
+    pub struct AsF64<D>(pub D);
+
+    impl<D: Distribution<u64>> Distribution<f64> for AsF64<D> {
+        fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> f64 {
+            self.0.sample(rng) as f64
+        }
+    }
+}
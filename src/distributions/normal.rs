@@ -0,0 +1,75 @@
+// Written by Alexander Stocko <as@coder.gg>
+//
+// To the extent possible under law, the author has dedicated all copyright
+// and related and neighboring rights to this software to the public domain
+// worldwide. This software is distributed without any warranty.
+//
+// See <LICENSE or http://creativecommons.org/publicdomain/zero/1.0/>
+
+use rand::Rng;
+use float::f64_open_from_u64;
+use super::{ziggurat, Distribution};
+use super::ziggurat_tables::{ZIG_NORM_F, ZIG_NORM_R, ZIG_NORM_X};
+
+/// The standard normal distribution, with mean 0 and standard deviation 1.
+///
+/// Sampled with the ziggurat method; about 99% of draws take a single
+/// `next_u64`. Scale and shift the result for other parameters.
+#[derive(Clone, Copy, Debug)]
+pub struct StandardNormal;
+
+impl Distribution<f64> for StandardNormal {
+    #[inline]
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> f64 {
+        #[inline]
+        fn pdf(x: f64) -> f64 {
+            (-x * x / 2.0).exp()
+        }
+
+        // Marsaglia's method for the tail beyond ZIG_NORM_R.
+        #[inline]
+        fn zero_case<R: Rng + ?Sized>(rng: &mut R, u: f64) -> f64 {
+            let mut x;
+            loop {
+                x = f64_open_from_u64(rng.next_u64()).ln() / ZIG_NORM_R;
+                let y = f64_open_from_u64(rng.next_u64()).ln();
+                if -2.0 * y >= x * x {
+                    break;
+                }
+            }
+            if u < 0.0 { x - ZIG_NORM_R } else { ZIG_NORM_R - x }
+        }
+
+        ziggurat(rng, true, &ZIG_NORM_X, &ZIG_NORM_F, pdf, zero_case)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use distributions::Distribution;
+    use distributions::tests::{assert_pinned_close, moments, Words};
+    use test::{pinned, xoroshiro, xorshift1024};
+    use super::StandardNormal;
+    #[test]
+    fn test() {
+        assert_pinned_close(&StandardNormal, &mut pinned(),
+                            &[-0.25109940909233713, 1.5712022673446642, -0.019745792331579026,
+                              -0.9643152654819935, 0.255230426368946, 0.2905744232486064]);
+
+        let (mean, var) = moments(&StandardNormal, &mut xoroshiro(), 100000);
+        assert!(mean.abs() < 0.01 && (var - 1.0).abs() < 0.02);
+
+        let (mean, var) = moments(&StandardNormal, &mut xorshift1024(), 100000);
+        assert!(mean.abs() < 0.01 && (var - 1.0).abs() < 0.02);
+    }
+
+    #[test]
+    fn test_tail() {
+        // Layer 0 with the largest fraction falls through to the tail
+        let words = [0x007f_ffff_ffff_fff8u64, 1 << 63, 1 << 63];
+        let mut rng = Words(words.iter().cloned());
+        let x = StandardNormal.sample(&mut rng);
+        assert!(x > super::ZIG_NORM_R);
+    }
+}
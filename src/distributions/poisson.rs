@@ -0,0 +1,145 @@
+// Written by Alexander Stocko <as@coder.gg>
+//
+// To the extent possible under law, the author has dedicated all copyright
+// and related and neighboring rights to this software to the public domain
+// worldwide. This software is distributed without any warranty.
+//
+// See <LICENSE or http://creativecommons.org/publicdomain/zero/1.0/>
+
+use std::f64::consts::PI;
+use rand::Rng;
+use float::f64_from_u64;
+use super::Distribution;
+
+/// The Poisson distribution with mean `lambda`.
+///
+/// Means of at least 10 use Hörmann's transformed rejection with squeeze
+/// (PTRS) [1]; smaller means multiply uniforms until the product drops
+/// below `exp(-lambda)`.
+///
+/// [1]: Wolfgang Hörmann, [*The Transformed Rejection Method for
+/// Generating Poisson Random Variables*]
+/// (https://doi.org/10.1016/0167-6687(93)90997-4)
+#[derive(Clone, Copy, Debug)]
+pub struct Poisson {
+    lambda: f64,
+}
+
+impl Poisson {
+    /// Create a Poisson distribution.
+    ///
+    /// Panics if `lambda` is negative or not finite.
+    pub fn new(lambda: f64) -> Poisson {
+        assert!(lambda >= 0.0 && lambda.is_finite(), "Poisson::new called with lambda < 0.");
+        Poisson { lambda }
+    }
+}
+
+impl Distribution<u64> for Poisson {
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> u64 {
+        if self.lambda >= 10.0 {
+            ptrs(rng, self.lambda)
+        } else if self.lambda == 0.0 {
+            0
+        } else {
+            multiplication(rng, self.lambda)
+        }
+    }
+}
+
+fn multiplication<R: Rng + ?Sized>(rng: &mut R, lambda: f64) -> u64 {
+    let limit = (-lambda).exp();
+    let mut k = 0;
+    let mut prod = f64_from_u64(rng.next_u64());
+    while prod > limit {
+        k += 1;
+        prod *= f64_from_u64(rng.next_u64());
+    }
+    k
+}
+
+fn ptrs<R: Rng + ?Sized>(rng: &mut R, lambda: f64) -> u64 {
+    let log_lambda = lambda.ln();
+    let b = 0.931 + 2.53 * lambda.sqrt();
+    let a = -0.059 + 0.02483 * b;
+    let inv_alpha = 1.1239 + 1.1328 / (b - 3.4);
+    let v_r = 0.9277 - 3.6224 / (b - 2.0);
+
+    loop {
+        let u = f64_from_u64(rng.next_u64()) - 0.5;
+        let v = f64_from_u64(rng.next_u64());
+        let us = 0.5 - u.abs();
+        let k = ((2.0 * a / us + b) * u + lambda + 0.43).floor();
+        if us >= 0.07 && v <= v_r {
+            return k as u64;
+        }
+        if k < 0.0 || (us < 0.013 && v > us) {
+            continue;
+        }
+        if v.ln() + inv_alpha.ln() - (a / (us * us) + b).ln() <=
+           -lambda + k * log_lambda - log_gamma(k + 1.0) {
+            return k as u64;
+        }
+    }
+}
+
+/// Natural logarithm of the gamma function for `x >= 1`, from the
+/// Stirling series, shifting small arguments up to 7 first.
+pub(crate) fn log_gamma(x: f64) -> f64 {
+    const A: [f64; 10] = [8.333333333333333e-02, -2.777777777777778e-03,
+                          7.936507936507937e-04, -5.952380952380952e-04,
+                          8.417508417508418e-04, -1.917526917526918e-03,
+                          6.41025641025641e-03, -2.955065359477124e-02,
+                          1.796443723688307e-01, -1.39243221690590e+00];
+
+    if x == 1.0 || x == 2.0 {
+        return 0.0;
+    }
+
+    let n = if x < 7.0 { (7.0 - x).floor() } else { 0.0 };
+    let mut x0 = x + n;
+    let x2 = 1.0 / (x0 * x0);
+    let mut gl0 = A[9];
+    for a in A[..9].iter().rev() {
+        gl0 = gl0 * x2 + a;
+    }
+    let mut gl = gl0 / x0 + 0.5 * (2.0 * PI).ln() + (x0 - 0.5) * x0.ln() - x0;
+    for _ in 0..n as u32 {
+        gl -= (x0 - 1.0).ln();
+        x0 -= 1.0;
+    }
+    gl
+}
+
+
+#[cfg(test)]
+mod tests {
+    use distributions::Distribution;
+    use distributions::tests::{assert_pinned, moments, AsF64};
+    use test::{pinned, xoroshiro, xorshift1024};
+    use super::{log_gamma, Poisson};
+    #[test]
+    fn test() {
+        let mut rng = pinned();
+        assert_pinned(&Poisson::new(3.5), &mut rng,
+                      &[1, 8, 4, 2, 5, 2, 4, 4, 3, 6]);
+        assert_pinned(&Poisson::new(250.0), &mut rng,
+                      &[268, 267, 237, 265, 264, 254, 237, 238, 268, 241]);
+        assert_eq!(Poisson::new(0.0).sample(&mut rng), 0);
+
+        let dist = Poisson::new(3.5);
+        let (mean, var) = moments(&AsF64(dist), &mut xoroshiro(), 100000);
+        assert!((mean - 3.5).abs() < 0.03 && (var - 3.5).abs() < 0.1);
+
+        let dist = Poisson::new(250.0);
+        let (mean, var) = moments(&AsF64(dist), &mut xorshift1024(), 100000);
+        assert!((mean - 250.0).abs() < 0.3 && (var - 250.0).abs() < 5.0);
+    }
+
+    #[test]
+    fn test_log_gamma() {
+        assert!(log_gamma(1.0).abs() < 1e-15);
+        assert!((log_gamma(5.0) - 24f64.ln()).abs() < 1e-14);
+        assert!((log_gamma(21.0) - 2432902008176640000f64.ln()).abs() < 1e-12);
+    }
+}
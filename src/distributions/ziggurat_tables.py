@@ -0,0 +1,80 @@
+#!/usr/bin/env python3
+#
+# Written by Alexander Stocko <as@coder.gg>
+#
+# To the extent possible under law, the author has dedicated all copyright
+# and related and neighboring rights to this software to the public domain
+# worldwide. This software is distributed without any warranty.
+#
+# See <LICENSE or http://creativecommons.org/publicdomain/zero/1.0/>
+
+# Generates ziggurat_tables.rs. Run from this directory:
+#
+#     python3 ziggurat_tables.py > ziggurat_tables.rs
+
+from math import exp, sqrt, log
+
+TABLE_LEN = 256
+
+
+def norm_f(x):
+    return exp(-x * x / 2.0)
+
+
+def norm_f_inv(y):
+    return sqrt(-2.0 * log(y))
+
+
+def exp_f(x):
+    return exp(-x)
+
+
+def exp_f_inv(y):
+    return -log(y)
+
+
+# Right-most layer edge r and layer area v, from Marsaglia and Tsang.
+TABLES = [('ZIG_NORM', norm_f, norm_f_inv,
+           3.654152885361008796, 0.00492867323399),
+          ('ZIG_EXP', exp_f, exp_f_inv,
+           7.697117470131050077, 0.0039496598225815571993)]
+
+
+def tables(r, v, f, f_inv):
+    xvec = [0.0] * (TABLE_LEN + 1)
+    xvec[0] = v / f(r)
+    xvec[1] = r
+    for i in range(2, TABLE_LEN):
+        last = xvec[i - 1]
+        xvec[i] = f_inv(v / last + f(last))
+    fvec = [f(x) for x in xvec]
+    return xvec, fvec
+
+
+def render(name, values):
+    print('pub static %s: [f64; %d] = [' % (name, len(values)))
+    for v in values:
+        print('    %r,' % v)
+    print('];')
+
+
+print('''// Written by Alexander Stocko <as@coder.gg>
+//
+// To the extent possible under law, the author has dedicated all copyright
+// and related and neighboring rights to this software to the public domain
+// worldwide. This software is distributed without any warranty.
+//
+// See <LICENSE or http://creativecommons.org/publicdomain/zero/1.0/>
+
+// Tables for the ziggurat samplers, generated by ziggurat_tables.py.
+// Do not edit by hand.
+
+#![allow(clippy::unreadable_literal, clippy::excessive_precision)]
+''')
+
+for name, f, f_inv, r, v in TABLES:
+    print('pub const %s_R: f64 = %r;' % (name, r))
+    xvec, fvec = tables(r, v, f, f_inv)
+    render(name + '_X', xvec)
+    render(name + '_F', fvec)
+    print()
@@ -0,0 +1,74 @@
+// Written by Alexander Stocko <as@coder.gg>
+//
+// To the extent possible under law, the author has dedicated all copyright
+// and related and neighboring rights to this software to the public domain
+// worldwide. This software is distributed without any warranty.
+//
+// See <LICENSE or http://creativecommons.org/publicdomain/zero/1.0/>
+
+//! Seeding from operating system entropy.
+
+#[cfg(feature = "std")]
+use std::error::Error;
+use std::fmt;
+
+use getrandom;
+
+/// An error returned when the operating system entropy source could not
+/// be read.
+#[derive(Debug)]
+pub struct EntropyError(getrandom::Error);
+
+impl EntropyError {
+    /// The operating system error code, if the failure came from the
+    /// operating system.
+    pub fn raw_os_error(&self) -> Option<i32> {
+        self.0.raw_os_error()
+    }
+}
+
+impl fmt::Display for EntropyError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "could not read operating system entropy: {}", self.0)
+    }
+}
+
+#[cfg(feature = "std")]
+impl Error for EntropyError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(&self.0)
+    }
+}
+
+/// Fill `dest` with words read from the operating system entropy source.
+///
+/// Uses the platform's own interface through the `getrandom` crate, such
+/// as `getrandom(2)` on Linux, `BCryptGenRandom` on Windows and
+/// `crypto.getRandomValues` on the web.
+pub fn fill(dest: &mut [u64]) -> Result<(), EntropyError> {
+    let mut buf = [0u8; 128];
+    for chunk in dest.chunks_mut(16) {
+        let bytes = &mut buf[..chunk.len() * 8];
+        getrandom::getrandom(bytes).map_err(EntropyError)?;
+        for (word, b) in chunk.iter_mut().zip(bytes.chunks(8)) {
+            let mut le = [0u8; 8];
+            le.copy_from_slice(b);
+            *word = u64::from_le_bytes(le);
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::fill;
+    #[test]
+    fn test() {
+        let mut a = [0; 20];
+        let mut b = [0; 20];
+        fill(&mut a).unwrap();
+        fill(&mut b).unwrap();
+        assert!(a != b);
+        assert!(a.iter().all(|x| *x != 0));
+    }
+}
@@ -0,0 +1,100 @@
+// Written by Alexander Stocko <as@coder.gg>
+//
+// To the extent possible under law, the author has dedicated all copyright
+// and related and neighboring rights to this software to the public domain
+// worldwide. This software is distributed without any warranty.
+//
+// See <LICENSE or http://creativecommons.org/publicdomain/zero/1.0/>
+
+//! Extension methods for random number generators.
+
+use rand::Rng;
+
+use float;
+use uniform::{self, UniformInt};
+
+/// Extra generation methods built on `next_u64`, available on every `Rng`.
+///
+/// The methods only use the upper bits of each output, and their results
+/// are stable across crate versions.
+pub trait RngExt: Rng {
+    /// Return a random `bool` from the sign bit of the next `u64`.
+    ///
+    /// This is the Boolean extraction recommended for generators whose
+    /// lowest bits are LFSRs. Use `BitStream` to avoid spending a whole
+    /// `u64` per flip.
+    #[inline]
+    fn gen_bool_sign(&mut self) -> bool {
+        (self.next_u64() as i64) < 0
+    }
+
+    /// Return a uniformly distributed integer in [0, n), using Lemire's
+    /// nearly divisionless method.
+    ///
+    /// Panics if `n` is zero.
+    #[inline]
+    fn gen_below(&mut self, n: u64) -> u64 {
+        uniform::below_u64(self, n)
+    }
+
+    /// Return a uniformly distributed integer in [0, n).
+    ///
+    /// Panics if `n` is zero.
+    #[inline]
+    fn gen_below_u128(&mut self, n: u128) -> u128 {
+        uniform::below_u128(self, n)
+    }
+
+    /// Return a uniformly distributed integer in [low, high], for any
+    /// integer width up to 128 bits.
+    ///
+    /// Panics if `low` is greater than `high`.
+    #[inline]
+    fn gen_range_inclusive<T: UniformInt>(&mut self, low: T, high: T) -> T {
+        T::sample_inclusive(low, high, self)
+    }
+
+    /// Return the next random `f64` in the open interval (0, 1).
+    #[inline]
+    fn next_f64_open(&mut self) -> f64 {
+        float::f64_open_from_u64(self.next_u64())
+    }
+
+    /// Return the next random `f64` in the closed interval [0, 1].
+    #[inline]
+    fn next_f64_closed(&mut self) -> f64 {
+        float::f64_closed_from_u64(self.next_u64())
+    }
+
+    /// Return the next random `f64` in [0, 1), able to take every
+    /// representable value in that range.
+    ///
+    /// Usually consumes two `u64`s. See `float::f64_full`.
+    #[inline]
+    fn next_f64_full(&mut self) -> f64 {
+        float::f64_full(|| self.next_u64())
+    }
+
+    /// Return the next random `f32` in the open interval (0, 1).
+    #[inline]
+    fn next_f32_open(&mut self) -> f32 {
+        float::f32_open_from_u64(self.next_u64())
+    }
+
+    /// Return the next random `f32` in the closed interval [0, 1].
+    #[inline]
+    fn next_f32_closed(&mut self) -> f32 {
+        float::f32_closed_from_u64(self.next_u64())
+    }
+
+    /// Return the next random `f32` in [0, 1), able to take every
+    /// representable value in that range.
+    ///
+    /// Usually consumes two `u64`s. See `float::f32_full`.
+    #[inline]
+    fn next_f32_full(&mut self) -> f32 {
+        float::f32_full(|| self.next_u64())
+    }
+}
+
+impl<R: Rng + ?Sized> RngExt for R {}
@@ -0,0 +1,58 @@
+// Written by Alexander Stocko <as@coder.gg>
+//
+// To the extent possible under law, the author has dedicated all copyright
+// and related and neighboring rights to this software to the public domain
+// worldwide. This software is distributed without any warranty.
+//
+// See <LICENSE or http://creativecommons.org/publicdomain/zero/1.0/>
+
+//! Bulk fill loops for Xorshift1024*.
+//!
+//! Unrolling lets the compiler interleave successive steps through its
+//! state array. The smaller generators gain nothing over calling `next_u64`
+//! in a loop, so they have no bulk fill methods.
+
+const UNROLL: usize = 4;
+
+#[inline(always)]
+pub fn fill<T, F: FnMut() -> T>(dest: &mut [T], mut next: F) {
+    for x in dest {
+        *x = next();
+    }
+}
+
+/// Fill `dest` with the little-endian bytes of successive outputs. A
+/// trailing partial word uses the low bytes of one more output.
+#[inline(always)]
+pub fn fill_bytes<F: FnMut() -> u64>(dest: &mut [u8], mut next: F) {
+    for word in dest.chunks_mut(8) {
+        let bytes = next().to_le_bytes();
+        word.copy_from_slice(&bytes[..word.len()]);
+    }
+}
+
+/// Like `fill`, four outputs per iteration.
+#[inline(always)]
+pub fn fill_unrolled<T, F: FnMut() -> T>(dest: &mut [T], mut next: F) {
+    let mut chunks = dest.chunks_exact_mut(UNROLL);
+    for chunk in &mut chunks {
+        chunk[0] = next();
+        chunk[1] = next();
+        chunk[2] = next();
+        chunk[3] = next();
+    }
+    fill(chunks.into_remainder(), next)
+}
+
+/// Like `fill_bytes`, four outputs per iteration.
+#[inline(always)]
+pub fn fill_bytes_unrolled<F: FnMut() -> u64>(dest: &mut [u8], mut next: F) {
+    let mut chunks = dest.chunks_exact_mut(8 * UNROLL);
+    for chunk in &mut chunks {
+        chunk[0..8].copy_from_slice(&next().to_le_bytes());
+        chunk[8..16].copy_from_slice(&next().to_le_bytes());
+        chunk[16..24].copy_from_slice(&next().to_le_bytes());
+        chunk[24..32].copy_from_slice(&next().to_le_bytes());
+    }
+    fill_bytes(chunks.into_remainder(), next)
+}
@@ -0,0 +1,147 @@
+// Written by Alexander Stocko <as@coder.gg>
+//
+// To the extent possible under law, the author has dedicated all copyright
+// and related and neighboring rights to this software to the public domain
+// worldwide. This software is distributed without any warranty.
+//
+// See <LICENSE or http://creativecommons.org/publicdomain/zero/1.0/>
+
+//! Conversion of generator outputs to floating-point numbers.
+//!
+//! All conversions use the upper bits of each `u64`, as recommended for
+//! the `+` and `*` scramblers, whose lowest bits are LFSRs.
+
+/// Convert the upper 53 bits of `x` to an `f64` in [0, 1).
+#[inline(always)]
+pub fn f64_from_u64(x: u64) -> f64 {
+    (x >> 11) as f64 * (1.0 / (1u64 << 53) as f64)
+}
+
+/// Convert the upper 24 bits of `x` to an `f32` in [0, 1).
+#[inline(always)]
+pub fn f32_from_u64(x: u64) -> f32 {
+    (x >> 40) as f32 * (1.0 / (1u32 << 24) as f32)
+}
+
+/// Convert the upper 52 bits of `x` to an `f64` in (0, 1), centred in
+/// each of the 2^52 equally spaced intervals.
+#[inline(always)]
+pub fn f64_open_from_u64(x: u64) -> f64 {
+    ((x >> 12) as f64 + 0.5) * (1.0 / (1u64 << 52) as f64)
+}
+
+/// Convert the upper 23 bits of `x` to an `f32` in (0, 1), centred in
+/// each of the 2^23 equally spaced intervals.
+#[inline(always)]
+pub fn f32_open_from_u64(x: u64) -> f32 {
+    ((x >> 41) as f32 + 0.5) * (1.0 / (1u32 << 23) as f32)
+}
+
+/// Convert the upper 53 bits of `x` to an `f64` in [0, 1], with both end
+/// points reachable.
+#[inline(always)]
+pub fn f64_closed_from_u64(x: u64) -> f64 {
+    (x >> 11) as f64 / ((1u64 << 53) - 1) as f64
+}
+
+/// Convert the upper 24 bits of `x` to an `f32` in [0, 1], with both end
+/// points reachable.
+#[inline(always)]
+pub fn f32_closed_from_u64(x: u64) -> f32 {
+    (x >> 40) as f32 / ((1u32 << 24) - 1) as f32
+}
+
+// Number of leading zero bits in an endless stream of random words, plus
+// one, giving up once `limit` is exceeded.
+#[inline]
+fn geometric<F: FnMut() -> u64>(next: &mut F, limit: u32) -> Option<u32> {
+    let mut k = 1;
+    loop {
+        let x = next();
+        if x != 0 {
+            return Some(k + x.leading_zeros());
+        }
+        k += 64;
+        if k > limit {
+            return None;
+        }
+    }
+}
+
+/// Generate an `f64` in [0, 1) that can take every representable value,
+/// following Downey [1].
+///
+/// The exponent is drawn from a geometric distribution and the mantissa is
+/// filled with 52 random bits, which is equivalent to rounding a uniform
+/// real number down to the nearest representable `f64`. Values below
+/// 2^-1022 are flushed to zero; they occur with probability 2^-1022.
+///
+/// [1]: Allen B. Downey, [*Generating Pseudo-random Floating-Point Values*]
+/// (http://allendowney.com/research/rand/)
+#[inline]
+pub fn f64_full<F: FnMut() -> u64>(mut next: F) -> f64 {
+    match geometric(&mut next, 1022) {
+        Some(k) if k <= 1022 => {
+            let mantissa = next() >> 12;
+            f64::from_bits(((1023 - k) as u64) << 52 | mantissa)
+        }
+        _ => 0.0,
+    }
+}
+
+/// Generate an `f32` in [0, 1) that can take every representable value.
+///
+/// See `f64_full`. Values below 2^-126 are flushed to zero.
+#[inline]
+pub fn f32_full<F: FnMut() -> u64>(mut next: F) -> f32 {
+    match geometric(&mut next, 126) {
+        Some(k) if k <= 126 => {
+            let mantissa = (next() >> 41) as u32;
+            f32::from_bits((127 - k) << 23 | mantissa)
+        }
+        _ => 0.0,
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn test() {
+        assert_eq!(f64_from_u64(0), 0.0);
+        assert_eq!(f64_from_u64(!0), 1.0 - 2f64.powi(-53));
+        assert_eq!(f32_from_u64(!0), 1.0 - 2f32.powi(-24));
+        assert_eq!(f64_open_from_u64(0), 2f64.powi(-53));
+        assert_eq!(f64_open_from_u64(!0), 1.0 - 2f64.powi(-53));
+        assert_eq!(f32_open_from_u64(0), 2f32.powi(-24));
+        assert_eq!(f32_open_from_u64(!0), 1.0 - 2f32.powi(-24));
+        assert_eq!(f64_closed_from_u64(0), 0.0);
+        assert_eq!(f64_closed_from_u64(!0), 1.0);
+        assert_eq!(f32_closed_from_u64(!0), 1.0);
+
+        // Only the upper bits are used
+        assert_eq!(f64_from_u64(0x7ff), 0.0);
+        assert_eq!(f32_from_u64(0xff_ffff_ffff), 0.0);
+    }
+
+    #[test]
+    fn test_full() {
+        let words = [1u64 << 63, !0];
+        let mut it = words.iter().cloned();
+        assert_eq!(f64_full(|| it.next().unwrap()), 1.0 - 2f64.powi(-53));
+
+        // 70 leading zeros, then a zero mantissa
+        let words = [0u64, 1 << 57, 0];
+        let mut it = words.iter().cloned();
+        assert_eq!(f64_full(|| it.next().unwrap()), 2f64.powi(-71));
+
+        let words = [1u64 << 62, 1 << 63];
+        let mut it = words.iter().cloned();
+        assert_eq!(f32_full(|| it.next().unwrap()), 0.375);
+
+        let mut it = (0..).map(|_| 0);
+        assert_eq!(f64_full(|| it.next().unwrap()), 0.0);
+        assert_eq!(f32_full(|| it.next().unwrap()), 0.0);
+    }
+}
@@ -0,0 +1,128 @@
+// Written by Alexander Stocko <as@coder.gg>
+//
+// To the extent possible under law, the author has dedicated all copyright
+// and related and neighboring rights to this software to the public domain
+// worldwide. This software is distributed without any warranty.
+//
+// See <LICENSE or http://creativecommons.org/publicdomain/zero/1.0/>
+
+//! Adapters selecting how `u32`s are taken from `u64` outputs.
+//!
+//! The generators return the upper half of each `u64` from `next_u32`,
+//! since the lowest bits of xoroshiro128+, xorshift128+ and xorshift1024*
+//! are LFSRs.
+
+use rand::Rng;
+
+/// Compatibility mode returning the lower half of each `u64` from
+/// `next_u32`.
+///
+/// Versions 0.1.x truncated `next_u64` to produce `next_u32`, and used the
+/// default float conversions of `rand`, which take the lower bits. Wrap a
+/// generator in `LegacyU32` to reproduce `u32` and float streams recorded
+/// with those versions.
+///
+/// ```rust
+/// use xorshift::{LegacyU32, Rng, Xoroshiro128};
+///
+/// let mut rng = LegacyU32(Xoroshiro128::new([1, 2]));
+/// assert_eq!(rng.next_u32(), 3);
+/// ```
+#[derive(Copy, Clone, Debug)]
+pub struct LegacyU32<R>(pub R);
+
+impl<R: Rng> Rng for LegacyU32<R> {
+    #[inline]
+    fn next_u32(&mut self) -> u32 {
+        self.0.next_u64() as u32
+    }
+
+    #[inline]
+    fn next_u64(&mut self) -> u64 {
+        self.0.next_u64()
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.0.fill_bytes(dest)
+    }
+}
+
+/// Returns both halves of each `u64` from successive `next_u32` calls,
+/// upper half first.
+///
+/// Doubles `u32` throughput, at the cost of also handing out the weaker low
+/// bits of generators whose `RngInfo::WEAK_LOW_BITS` is non-zero.
+#[derive(Copy, Clone, Debug)]
+pub struct HalvesU32<R> {
+    rng: R,
+    low: Option<u32>,
+}
+
+impl<R> HalvesU32<R> {
+    /// Wrap a generator.
+    pub fn new(rng: R) -> HalvesU32<R> {
+        HalvesU32 { rng, low: None }
+    }
+
+    /// Unwrap the generator, discarding any buffered half.
+    pub fn into_inner(self) -> R {
+        self.rng
+    }
+}
+
+impl<R: Rng> Rng for HalvesU32<R> {
+    #[inline]
+    fn next_u32(&mut self) -> u32 {
+        match self.low.take() {
+            Some(low) => low,
+            None => {
+                let x = self.rng.next_u64();
+                self.low = Some(x as u32);
+                (x >> 32) as u32
+            }
+        }
+    }
+
+    #[inline]
+    fn next_u64(&mut self) -> u64 {
+        self.rng.next_u64()
+    }
+
+    #[inline]
+    fn next_f32(&mut self) -> f32 {
+        self.rng.next_f32()
+    }
+
+    #[inline]
+    fn next_f64(&mut self) -> f64 {
+        self.rng.next_f64()
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.rng.fill_bytes(dest)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use rand::Rng;
+    use xoroshiro128::Xoroshiro128;
+    use super::{HalvesU32, LegacyU32};
+    #[test]
+    fn test() {
+        let base = Xoroshiro128::new([1477776328140003287, 1477776328140003287]);
+        let mut rng = base;
+        let vals = rng.gen_iter::<u64>().take(50).collect::<Vec<u64>>();
+
+        let mut rng = base;
+        let mut legacy = LegacyU32(base);
+        let mut halves = HalvesU32::new(base);
+        for x in vals {
+            assert_eq!(rng.next_u32(), (x >> 32) as u32);
+            assert_eq!(legacy.next_u32(), x as u32);
+            assert_eq!(halves.next_u32(), (x >> 32) as u32);
+            assert_eq!(halves.next_u32(), x as u32);
+        }
+    }
+}
@@ -0,0 +1,184 @@
+// Written by Alexander Stocko <as@coder.gg>
+//
+// To the extent possible under law, the author has dedicated all copyright
+// and related and neighboring rights to this software to the public domain
+// worldwide. This software is distributed without any warranty.
+//
+// See <LICENSE or http://creativecommons.org/publicdomain/zero/1.0/>
+
+//! Bijective 64-bit mixing and a fast hasher built on it.
+//!
+//! `mix64` is the output function of splitmix64, the variant 13 finalizer
+//! of Stafford [1]. Each step is invertible, so `unmix64` recovers the
+//! input exactly, which makes the pair useful for scrambling sequential
+//! IDs reversibly.
+//!
+//! ```rust
+//! use xorshift::{mix64, unmix64};
+//!
+//! let id = 12345;
+//! let token = mix64(id);
+//! assert_eq!(unmix64(token), id);
+//! ```
+//!
+//! [1]: David Stafford, [*Better Bit Mixing*]
+//! (http://zimbry.blogspot.com/2011/09/better-bit-mixing-improving-on.html)
+
+use std::hash::{BuildHasher, Hasher};
+
+/// Mix the bits of `z` with the splitmix64 finalizer.
+#[inline]
+pub const fn mix64(mut z: u64) -> u64 {
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9_u64);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB_u64);
+    z ^ (z >> 31)
+}
+
+/// Invert `mix64`, so that `unmix64(mix64(z)) == z`.
+///
+/// Uses the inverses of the multipliers modulo 2^64 and undoes each
+/// xor-shift by repeated shifting.
+#[inline]
+pub const fn unmix64(mut z: u64) -> u64 {
+    z = z ^ (z >> 31) ^ (z >> 62);
+    z = z.wrapping_mul(0x319642B2D24D8EC3_u64);
+    z = z ^ (z >> 27) ^ (z >> 54);
+    z = z.wrapping_mul(0x96DE1B173F119089_u64);
+    z ^ (z >> 30) ^ (z >> 60)
+}
+
+/// A fast, non-cryptographic `Hasher` for integer keys.
+///
+/// Each integer written is added to the state, which is then mixed with
+/// `mix64`. A single `u64` key `k` written to a hasher with seed 0 hashes
+/// to `mix64(k)`. Byte slices are read as little-endian words, with the
+/// last word padded with zeros.
+///
+/// It offers no protection against deliberately colliding keys.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct Mix64Hasher(u64);
+
+impl Mix64Hasher {
+    /// Create a hasher with the given initial state.
+    pub const fn new(seed: u64) -> Mix64Hasher {
+        Mix64Hasher(seed)
+    }
+}
+
+impl Hasher for Mix64Hasher {
+    #[inline]
+    fn finish(&self) -> u64 {
+        self.0
+    }
+
+    #[inline]
+    fn write(&mut self, bytes: &[u8]) {
+        for chunk in bytes.chunks(8) {
+            let mut word = [0; 8];
+            word[..chunk.len()].copy_from_slice(chunk);
+            self.write_u64(u64::from_le_bytes(word));
+        }
+    }
+
+    #[inline]
+    fn write_u8(&mut self, x: u8) {
+        self.write_u64(x as u64);
+    }
+
+    #[inline]
+    fn write_u16(&mut self, x: u16) {
+        self.write_u64(x as u64);
+    }
+
+    #[inline]
+    fn write_u32(&mut self, x: u32) {
+        self.write_u64(x as u64);
+    }
+
+    #[inline]
+    fn write_u64(&mut self, x: u64) {
+        self.0 = mix64(self.0.wrapping_add(x));
+    }
+
+    #[inline]
+    fn write_usize(&mut self, x: usize) {
+        self.write_u64(x as u64);
+    }
+}
+
+/// A `BuildHasher` for `Mix64Hasher`, for use with `HashMap` and `HashSet`.
+///
+/// ```rust
+/// use std::collections::HashMap;
+/// use xorshift::BuildMix64Hasher;
+///
+/// let mut map = HashMap::with_hasher(BuildMix64Hasher::default());
+/// map.insert(42u64, "answer");
+/// assert_eq!(map[&42], "answer");
+/// ```
+#[derive(Copy, Clone, Debug, Default)]
+pub struct BuildMix64Hasher(u64);
+
+impl BuildMix64Hasher {
+    /// Create a builder whose hashers start from `seed`.
+    pub const fn new(seed: u64) -> BuildMix64Hasher {
+        BuildMix64Hasher(seed)
+    }
+}
+
+impl BuildHasher for BuildMix64Hasher {
+    type Hasher = Mix64Hasher;
+
+    #[inline]
+    fn build_hasher(&self) -> Mix64Hasher {
+        Mix64Hasher(self.0)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+    use std::hash::{BuildHasher, Hasher};
+    use splitmix64::SplitMix64;
+    use super::{mix64, unmix64, BuildMix64Hasher, Mix64Hasher};
+    #[test]
+    fn test() {
+        // The first splitmix64 output is the mixed, incremented seed
+        let seed: u64 = 1477776061723855037;
+        assert_eq!(mix64(seed.wrapping_add(0x9E3779B97F4A7C15)), 1985237415132408290);
+        assert_eq!(unmix64(1985237415132408290), seed.wrapping_add(0x9E3779B97F4A7C15));
+
+        let mut rng = SplitMix64::new(seed);
+        for _ in 0..10000 {
+            let x = rng.next_u64();
+            assert_eq!(unmix64(mix64(x)), x);
+            assert_eq!(mix64(unmix64(x)), x);
+        }
+        for x in [0, 1, u64::MAX, 1 << 63].iter().cloned() {
+            assert_eq!(unmix64(mix64(x)), x);
+        }
+    }
+
+    #[test]
+    fn test_hasher() {
+        let mut hasher = Mix64Hasher::default();
+        hasher.write_u64(42);
+        assert_eq!(hasher.finish(), mix64(42));
+
+        // Integer widths hash alike, and bytes are little-endian words
+        let build = BuildMix64Hasher::new(7);
+        let mut a = build.build_hasher();
+        a.write_u32(42);
+        let mut b = build.build_hasher();
+        b.write(&[42, 0, 0]);
+        assert_eq!(a.finish(), b.finish());
+
+        let hashes = (0..1000u64).map(|x| {
+            let mut hasher = build.build_hasher();
+            hasher.write_u64(x);
+            hasher.finish() >> 54
+        }).collect::<HashSet<u64>>();
+        assert!(hashes.len() > 600);
+    }
+}
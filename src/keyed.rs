@@ -0,0 +1,166 @@
+// Written by Alexander Stocko <as@coder.gg>
+//
+// To the extent possible under law, the author has dedicated all copyright
+// and related and neighboring rights to this software to the public domain
+// worldwide. This software is distributed without any warranty.
+//
+// See <LICENSE or http://creativecommons.org/publicdomain/zero/1.0/>
+
+//! Counter-based random values addressed by a key and coordinates.
+
+use bounded;
+use float;
+use hash::mix64;
+use splitmix64::SplitMix64;
+
+const GAMMA: u64 = 0x9E3779B97F4A7C15;
+
+/// Stateless random values addressed by a key and a list of coordinates,
+/// such as a world seed and a grid position.
+///
+/// Each coordinate `c` advances a splitmix64 counter by `c + 1` gamma
+/// increments and applies the finalizer, and the result keys the next
+/// coordinate. So `value(key, &[i])` is output `i` of
+/// `SplitMix64::new(key)`, and `value(key, &[x, y])` equals
+/// `value(value(key, &[x]), &[y])`. With no coordinates the key is
+/// returned unchanged.
+///
+/// ```rust
+/// use xorshift::KeyedSplitMix;
+///
+/// let seed = 42;
+/// let height = KeyedSplitMix::value_f64(seed, &[10, 20]);
+/// let loot = KeyedSplitMix::value_below(seed, &[10, 20, 3], 6);
+/// assert_eq!(height, KeyedSplitMix::value_f64(seed, &[10, 20]));
+/// assert!(loot < 6);
+/// ```
+#[derive(Copy, Clone, Debug)]
+pub struct KeyedSplitMix;
+
+impl KeyedSplitMix {
+    /// Return the random `u64` at `coords` under `key`.
+    pub const fn value(key: u64, coords: &[u64]) -> u64 {
+        let mut h = key;
+        let mut i = 0;
+        while i < coords.len() {
+            h = mix64(h.wrapping_add(coords[i].wrapping_add(1).wrapping_mul(GAMMA)));
+            i += 1;
+        }
+        h
+    }
+
+    /// Return a random `f64` in [0, 1) at `coords` under `key`.
+    #[inline]
+    pub fn value_f64(key: u64, coords: &[u64]) -> f64 {
+        float::f64_from_u64(KeyedSplitMix::value(key, coords))
+    }
+
+    /// Return a random `f32` in [0, 1) at `coords` under `key`.
+    #[inline]
+    pub fn value_f32(key: u64, coords: &[u64]) -> f32 {
+        float::f32_from_u64(KeyedSplitMix::value(key, coords))
+    }
+
+    /// Return a uniformly distributed integer in [0, n) at `coords` under
+    /// `key`.
+    ///
+    /// Uses Lemire's method on the value. In the rare case that it is
+    /// rejected, further words are drawn from a splitmix64 generator seeded
+    /// with the value. Panics if `n` is zero.
+    pub fn value_below(key: u64, coords: &[u64], n: u64) -> u64 {
+        assert!(n != 0, "value_below called with a zero bound.");
+
+        let x = KeyedSplitMix::value(key, coords);
+        let mut rng = SplitMix64::new(x);
+        bounded::below(x, n, || rng.next_u64())
+    }
+
+    /// Return a uniformly distributed integer in [low, high] at `coords`
+    /// under `key`.
+    ///
+    /// Panics if `low` is greater than `high`.
+    pub fn value_range_inclusive(key: u64, coords: &[u64], low: i64, high: i64) -> i64 {
+        assert!(low <= high, "value_range_inclusive called with low > high.");
+        let range = high.wrapping_sub(low) as u64;
+        let offset = match range.checked_add(1) {
+            Some(n) => KeyedSplitMix::value_below(key, coords, n),
+            None => KeyedSplitMix::value(key, coords),
+        };
+        low.wrapping_add(offset as i64)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use splitmix64::SplitMix64;
+    use super::KeyedSplitMix;
+    #[test]
+    fn test() {
+        // Pinned so that values stay stable across releases
+        let vals = (0..4).map(|x| KeyedSplitMix::value(1477776061723855037, &[x, 7]))
+            .collect::<Vec<u64>>();
+        assert_eq!(vals,
+                   vec![9899169398066480001, 17428881854551780295, 11206871194332352586,
+                        4134505819618916369]);
+
+        let mut rng = SplitMix64::new(1477776061723855037);
+        for i in 0..100 {
+            assert_eq!(KeyedSplitMix::value(1477776061723855037, &[i]), rng.next_u64());
+        }
+
+        let xy = KeyedSplitMix::value(5, &[3, 4]);
+        assert_eq!(KeyedSplitMix::value(KeyedSplitMix::value(5, &[3]), &[4]), xy);
+        assert!(KeyedSplitMix::value(5, &[4, 3]) != xy);
+        assert!(KeyedSplitMix::value(5, &[3, 4, 0]) != xy);
+        assert_eq!(KeyedSplitMix::value(5, &[]), 5);
+
+        let mut seen = [0; 6];
+        for x in 0..6000 {
+            seen[KeyedSplitMix::value_below(9, &[x], 6) as usize] += 1;
+            let r = KeyedSplitMix::value_range_inclusive(9, &[x], -3, 3);
+            assert!((-3..=3).contains(&r));
+        }
+        assert!(seen.iter().all(|n| *n > 900 && *n < 1100));
+        assert!(KeyedSplitMix::value_range_inclusive(9, &[1], i64::MIN, i64::MAX) != 0);
+    }
+
+    // Pearson correlation of the pairs, and the mean number of differing
+    // bits between their integer values.
+    fn stats<F: Fn(u64, u64) -> [u64; 3]>(neighbour: F) -> (f64, f64) {
+        let (mut sx, mut sy, mut sxx, mut syy, mut sxy, mut bits) = (0.0, 0.0, 0.0, 0.0, 0.0, 0);
+        let n = 256 * 256;
+        for x in 0..256 {
+            for y in 0..256 {
+                let a = KeyedSplitMix::value(11, &[x, y, 0]);
+                let b = KeyedSplitMix::value(11, &neighbour(x, y));
+                bits += (a ^ b).count_ones();
+                let (u, v) = (::float::f64_from_u64(a), ::float::f64_from_u64(b));
+                sx += u;
+                sy += v;
+                sxx += u * u;
+                syy += v * v;
+                sxy += u * v;
+            }
+        }
+        let n_f = n as f64;
+        let cov = sxy / n_f - sx / n_f * sy / n_f;
+        let var_x = sxx / n_f - sx / n_f * sx / n_f;
+        let var_y = syy / n_f - sy / n_f * sy / n_f;
+        (cov / (var_x * var_y).sqrt(), bits as f64 / n_f)
+    }
+
+    #[test]
+    fn test_adjacent() {
+        // Neighbours along each axis are uncorrelated, and differ in about
+        // half of their bits. The standard error of r is about 0.004.
+        let neighbours: [fn(u64, u64) -> [u64; 3]; 3] = [|x, y| [x + 1, y, 0],
+                                                         |x, y| [x, y + 1, 0],
+                                                         |x, y| [x, y, 1]];
+        for neighbour in neighbours.iter() {
+            let (r, bits) = stats(neighbour);
+            assert!(r.abs() < 0.02, "correlation {}", r);
+            assert!((bits - 32.0).abs() < 0.1, "mean differing bits {}", bits);
+        }
+    }
+}
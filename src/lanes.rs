@@ -0,0 +1,233 @@
+// Written by Alexander Stocko <as@coder.gg>
+//
+// To the extent possible under law, the author has dedicated all copyright
+// and related and neighboring rights to this software to the public domain
+// worldwide. This software is distributed without any warranty.
+//
+// See <LICENSE or http://creativecommons.org/publicdomain/zero/1.0/>
+
+//! Machinery shared by the multi-lane generators.
+//!
+//! Each generator with two words of state supplies a `Kernel` holding its
+//! step function and SIMD loops. `multi_lane!` builds the buffered four and
+//! eight lane types around it.
+
+/// The step function of a generator with two words of state, and the SIMD
+/// loops that apply it to several lanes at once.
+pub trait Kernel {
+    /// Advance one lane and return its output.
+    fn step(s0: &mut u64, s1: &mut u64) -> u64;
+
+    /// Fill `dest`, whose length is a multiple of 4, with interleaved
+    /// outputs of four lanes.
+    ///
+    /// The caller must check that AVX2 is available.
+    #[cfg(all(feature = "std", target_arch = "x86_64"))]
+    unsafe fn fill_avx2(s0: &mut [u64; 4], s1: &mut [u64; 4], dest: &mut [u64]);
+
+    /// Fill `dest`, whose length is a multiple of 8, with interleaved
+    /// outputs of eight lanes.
+    ///
+    /// The caller must check that AVX-512F is available.
+    #[cfg(all(feature = "std", feature = "avx512", target_arch = "x86_64"))]
+    unsafe fn fill_avx512(s0: &mut [u64; 8], s1: &mut [u64; 8], dest: &mut [u64]);
+}
+
+/// Step every lane once, writing one output per lane to `out`.
+#[inline(always)]
+pub fn step<K: Kernel, const N: usize>(s0: &mut [u64; N], s1: &mut [u64; N], out: &mut [u64]) {
+    for ((a, b), x) in s0.iter_mut().zip(s1.iter_mut()).zip(out) {
+        *x = K::step(a, b);
+    }
+}
+
+/// Fill `dest`, whose length is a multiple of `N`, with interleaved lane
+/// outputs.
+#[inline(always)]
+pub fn fill_scalar<K: Kernel, const N: usize>(s0: &mut [u64; N],
+                                              s1: &mut [u64; N],
+                                              dest: &mut [u64]) {
+    for block in dest.chunks_exact_mut(N) {
+        step::<K, N>(s0, s1, block);
+    }
+}
+
+/// Fill with four lanes, using AVX2 when it is detected at runtime.
+pub fn fill_x4<K: Kernel>(s0: &mut [u64; 4], s1: &mut [u64; 4], dest: &mut [u64]) {
+    #[cfg(all(feature = "std", target_arch = "x86_64"))]
+    {
+        if is_x86_feature_detected!("avx2") {
+            return unsafe { K::fill_avx2(s0, s1, dest) };
+        }
+    }
+    fill_scalar::<K, 4>(s0, s1, dest)
+}
+
+/// Fill with eight lanes, using AVX-512 when the `avx512` feature is on and
+/// it is detected at runtime.
+pub fn fill_x8<K: Kernel>(s0: &mut [u64; 8], s1: &mut [u64; 8], dest: &mut [u64]) {
+    #[cfg(all(feature = "std", feature = "avx512", target_arch = "x86_64"))]
+    {
+        if is_x86_feature_detected!("avx512f") {
+            return unsafe { K::fill_avx512(s0, s1, dest) };
+        }
+    }
+    fill_scalar::<K, 8>(s0, s1, dest)
+}
+
+/// Define a multi-lane generator over a scalar base generator and its
+/// `Kernel`. The base must provide `state` and `jumped`, and `$fill` is
+/// `fill_x4` or `fill_x8` to match the lane count.
+macro_rules! multi_lane {
+    ($(#[$attr:meta])*
+     pub struct $name:ident($base:ident, $kernel:ident, $n:expr, $fill:ident);) => {
+        $(#[$attr])*
+        #[derive(Copy, Clone)]
+        pub struct $name {
+            s0: [u64; $n],
+            s1: [u64; $n],
+            buf: [u64; $n],
+            pos: usize,
+        }
+
+        impl $name {
+            /// Create the generator from a base generator. Lane `i` is the
+            /// base generator jumped `i` times.
+            pub fn new(base: $base) -> $name {
+                let mut s0 = [0; $n];
+                let mut s1 = [0; $n];
+                let mut rng = base;
+                for i in 0..$n {
+                    let state = rng.state();
+                    s0[i] = state[0];
+                    s1[i] = state[1];
+                    rng = rng.jumped();
+                }
+                $name {
+                    s0,
+                    s1,
+                    buf: [0; $n],
+                    pos: $n,
+                }
+            }
+
+            /// Return the next random `u64` in interleaved lane order.
+            #[inline]
+            pub fn next_u64(&mut self) -> u64 {
+                if self.pos == $n {
+                    $crate::lanes::step::<$kernel, $n>(&mut self.s0, &mut self.s1, &mut self.buf);
+                    self.pos = 0;
+                }
+                self.pos += 1;
+                self.buf[self.pos - 1]
+            }
+
+            /// Fill `dest` with random `u64`s in interleaved lane order.
+            ///
+            /// Equivalent to calling `next_u64` for each element.
+            pub fn fill_u64(&mut self, dest: &mut [u64]) {
+                let buffered = ($n - self.pos).min(dest.len());
+                let (head, rest) = dest.split_at_mut(buffered);
+                head.copy_from_slice(&self.buf[self.pos..self.pos + buffered]);
+                self.pos += buffered;
+
+                let blocks = rest.len() - rest.len() % $n;
+                let (body, tail) = rest.split_at_mut(blocks);
+                $crate::lanes::$fill::<$kernel>(&mut self.s0, &mut self.s1, body);
+
+                for x in tail {
+                    *x = self.next_u64();
+                }
+            }
+        }
+
+        #[cfg(feature = "std")]
+        impl ::rand::Rng for $name {
+            #[inline]
+            fn next_u32(&mut self) -> u32 {
+                (self.next_u64() >> 32) as u32
+            }
+
+            #[inline]
+            fn next_u64(&mut self) -> u64 {
+                $name::next_u64(self)
+            }
+
+            #[inline]
+            fn next_f32(&mut self) -> f32 {
+                $crate::float::f32_from_u64(self.next_u64())
+            }
+
+            #[inline]
+            fn next_f64(&mut self) -> f64 {
+                $crate::float::f64_from_u64(self.next_u64())
+            }
+        }
+    }
+}
+
+/// Check that lane `i` of a four and an eight lane generator equals the
+/// base generator after `i` jumps, through single draws, uneven bulk fills
+/// and the scalar kernel.
+#[cfg(test)]
+macro_rules! test_multi_lane {
+    ($base:ident, $kernel:ident, $x4:ident, $x8:ident) => {
+        const SEED: u64 = 1477776328140003287;
+
+        fn check_lanes(vals: &[u64], n: usize) {
+            let mut rng = $base::new([SEED, SEED]);
+            for lane in 0..n {
+                let mut scalar = rng;
+                for x in vals.iter().skip(lane).step_by(n) {
+                    assert_eq!(*x, scalar.next_u64());
+                }
+                rng = rng.jumped();
+            }
+        }
+
+        #[test]
+        fn test_x4() {
+            let base = $base::new([SEED, SEED]);
+
+            let mut rng = $x4::new(base);
+            let vals = (0..403).map(|_| rng.next_u64()).collect::<Vec<u64>>();
+            check_lanes(&vals, 4);
+
+            // Mix single draws with bulk fills of uneven length
+            let mut rng = $x4::new(base);
+            let mut bulk = vec![0; 403];
+            bulk[0] = rng.next_u64();
+            rng.fill_u64(&mut bulk[1..6]);
+            rng.fill_u64(&mut bulk[6..401]);
+            rng.fill_u64(&mut bulk[401..]);
+            assert_eq!(vals, bulk);
+
+            let $x4 { mut s0, mut s1, .. } = $x4::new(base);
+            let mut scalar = vec![0; 400];
+            $crate::lanes::fill_scalar::<$kernel, 4>(&mut s0, &mut s1, &mut scalar);
+            assert_eq!(&vals[..400], &scalar[..]);
+        }
+
+        #[test]
+        fn test_x8() {
+            let base = $base::new([SEED, SEED]);
+
+            let mut rng = $x8::new(base);
+            let vals = (0..805).map(|_| rng.next_u64()).collect::<Vec<u64>>();
+            check_lanes(&vals, 8);
+
+            let mut rng = $x8::new(base);
+            let mut bulk = vec![0; 805];
+            bulk[0] = rng.next_u64();
+            rng.fill_u64(&mut bulk[1..10]);
+            rng.fill_u64(&mut bulk[10..803]);
+            rng.fill_u64(&mut bulk[803..]);
+            assert_eq!(vals, bulk);
+
+            let $x8 { mut s0, mut s1, .. } = $x8::new(base);
+            let mut scalar = vec![0; 800];
+            $crate::lanes::fill_scalar::<$kernel, 8>(&mut s0, &mut s1, &mut scalar);
+            assert_eq!(&vals[..800], &scalar[..]);
+        }
+    }
+}
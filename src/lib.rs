@@ -13,7 +13,7 @@
 //! # Usage
 //! ```toml
 //! [dependencies]
-//! xorshift = "0.1"
+//! xorshift = "0.2"
 //! ```
 //! ```rust
 //! extern crate xorshift;
@@ -31,9 +31,8 @@
 //!     // Use the high-resolution performance counter for seeding
 //!     let now = precise_time_ns();
 //!
-//!     // Manually seed a Xorshift128+ PRNG
-//!     let states = [now, now];
-//!     let mut rng: Xorshift128 = SeedableRng::from_seed(&states[..]);
+//!     // Seed a Xorshift128+ PRNG from a single word of seed material
+//!     let mut rng = Xorshift128::from_seed_conditioned(&[now]);
 //!     println!("Xorshift128+ random u64: {}", rng.next_u64());
 //!
 //!     // Use a SplitMix64 PRNG to seed a Xoroshiro128+ PRNG
@@ -117,24 +116,118 @@
 //! }
 //! ```
 //!
+//! # Features
+//! The `std` feature is enabled by default and provides the `rand` trait
+//! implementations, `thread_rng()` and seeding from operating system
+//! entropy. Without it the crate is `no_std`, and the generators are used
+//! through their inherent `next_u64` and `next_u32` methods. The `alloc`
+//! feature adds `SeedSequence` to `no_std` builds, and the `getrandom`
+//! feature adds the `from_entropy` constructors without pulling in `rand`.
+//! The `avx512` feature enables the AVX-512 kernels of the eight lane
+//! generators, and needs Rust 1.89. The optional `serde` feature makes
+//! `AliasTable` serializable, and the optional `rayon` feature adds
+//! deterministic parallel generation.
+//!
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+#[cfg(not(feature = "std"))]
+extern crate core as std;
 
+#[cfg(all(feature = "alloc", not(feature = "std")))]
+extern crate alloc;
+
+#[cfg(feature = "std")]
 #[macro_use]
 extern crate lazy_static;
 
+#[cfg(feature = "std")]
 extern crate rand;
 
+#[cfg(feature = "rayon")]
+extern crate rayon;
+
+#[cfg(feature = "serde")]
+extern crate serde;
+
+#[cfg(feature = "getrandom")]
+extern crate getrandom;
+
+#[macro_use]
+mod lanes;
+
+mod bounded;
+mod fill;
+mod seed;
+
+#[cfg(target_has_atomic = "64")]
+pub mod atomic;
+#[cfg(feature = "getrandom")]
+pub mod entropy;
+pub mod float;
+pub mod hash;
+pub mod keyed;
+
+#[cfg(feature = "std")]
+pub mod bits;
+#[cfg(feature = "std")]
+pub mod distributions;
+#[cfg(feature = "std")]
+pub mod ext;
+#[cfg(feature = "std")]
+pub mod halves;
+#[cfg(feature = "std")]
+pub mod monte_carlo;
+#[cfg(feature = "std")]
+pub mod seq;
+#[cfg(feature = "std")]
+pub mod uniform;
+#[cfg(feature = "std")]
+pub mod variance;
+#[cfg(all(feature = "std", feature = "rayon"))]
+pub mod par;
+#[cfg(feature = "alloc")]
+pub mod seed_sequence;
+pub mod permutation;
 pub mod splitmix64;
 pub mod xoroshiro128;
+pub mod xoroshiro128x;
 pub mod xorshift128;
+pub mod xorshift128x;
 pub mod xorshift1024;
 
+#[cfg(feature = "std")]
+pub use bits::BitStream;
+#[cfg(feature = "getrandom")]
+pub use entropy::EntropyError;
+#[cfg(feature = "std")]
+pub use ext::RngExt;
+#[cfg(feature = "std")]
+pub use halves::{HalvesU32, LegacyU32};
+#[cfg(feature = "std")]
+pub use uniform::UniformInt;
+#[cfg(feature = "std")]
+pub use variance::{Antithetic, CommonStreams};
+#[cfg(all(feature = "std", feature = "rayon"))]
+pub use par::{par_fill_u64, ParRngIter};
+#[cfg(feature = "alloc")]
+pub use seed_sequence::SeedSequence;
+#[cfg(target_has_atomic = "64")]
+pub use atomic::AtomicSplitMix64;
+pub use hash::{mix64, unmix64, BuildMix64Hasher, Mix64Hasher};
+pub use keyed::KeyedSplitMix;
+pub use permutation::RandomPermutation;
 pub use splitmix64::SplitMix64;
 pub use xoroshiro128::Xoroshiro128;
+pub use xoroshiro128x::{Xoroshiro128x4, Xoroshiro128x8};
 pub use xorshift128::Xorshift128;
-pub use xorshift1024::Xorshift1024;
+pub use xorshift128x::{Xorshift128x4, Xorshift128x8};
+pub use xorshift1024::{Xorshift1024, Xorshift1024Block};
 
+#[cfg(feature = "std")]
 pub use rand::{Rand, Rng, SeedableRng, StdRng};
 
+#[cfg(feature = "std")]
 use std::sync::atomic::{AtomicUsize, Ordering};
 
 /// A random number generator with jumpable state.
@@ -146,14 +239,48 @@ pub trait RngJump {
     fn jump(&mut self, count: usize);
 }
 
+/// Static properties of a random number generator.
+///
+/// Periods and jump distances are given as base 2 logarithms, rounded up
+/// for full-period generators whose period is `2^n - 1`.
+///
+/// ```rust
+/// use xorshift::{RngInfo, Xoroshiro128};
+///
+/// // Number of non-overlapping jump streams available, as a power of two.
+/// const STREAMS_LOG2: u32 = match Xoroshiro128::JUMP_LOG2 {
+///     Some(jump) => Xoroshiro128::PERIOD_LOG2 - jump,
+///     None => 0,
+/// };
+/// assert_eq!(STREAMS_LOG2, 64);
+/// ```
+pub trait RngInfo {
+    /// Number of bits of generator state.
+    const STATE_BITS: u32;
+
+    /// Base 2 logarithm of the generator period.
+    const PERIOD_LOG2: u32;
+
+    /// Base 2 logarithm of the number of `next_u64` calls skipped by a
+    /// single `RngJump::jump`, or `None` if the generator cannot jump.
+    const JUMP_LOG2: Option<u32>;
+
+    /// Number of low output bits that are LFSRs and fail linearity tests.
+    const WEAK_LOW_BITS: u32;
+}
+
 
 /// Create a jumpable random number generator. Each call increments
 /// the generator jump state.
+#[cfg(feature = "std")]
+// lazy_static 0.2 expands to the deprecated `ONCE_INIT`.
+#[allow(deprecated)]
 pub fn thread_rng <'a, T: Rand+Rng+RngJump+SeedableRng<&'a [u64]>>() -> T {
     lazy_static! {
-        static ref THREAD_RNG_STATE : Vec<u64> = {
-            match StdRng::new() {
-                Ok(mut r) => r.gen_iter::<u64>().take(16).collect::<Vec<u64>>(),
+        static ref THREAD_RNG_STATE : [u64; 16] = {
+            let mut state = [0; 16];
+            match entropy::fill(&mut state) {
+                Ok(()) => state,
                 Err(e) => panic!("could not initialize seeding rng: {}", e)
             }
         };
@@ -167,9 +294,28 @@ pub fn thread_rng <'a, T: Rand+Rng+RngJump+SeedableRng<&'a [u64]>>() -> T {
 }
 
 
-// Taken from the lib.rs in the rand crate.
 #[cfg(test)]
 mod test {
+    use splitmix64::SplitMix64;
+    use xoroshiro128::Xoroshiro128;
+    use xorshift1024::Xorshift1024;
+
+    // The generator for pinned output vectors, which keep sampling stable
+    // across releases as described in `distributions`.
+    pub fn pinned() -> SplitMix64 {
+        SplitMix64::new(1477776061723855037)
+    }
+
+    // Generators for statistical checks, such as sample moments.
+    pub fn xoroshiro() -> Xoroshiro128 {
+        Xoroshiro128::from_seed_conditioned(&[1, 2])
+    }
+
+    pub fn xorshift1024() -> Xorshift1024 {
+        Xorshift1024::from_seed_conditioned(&[1, 2])
+    }
+
+    // Taken from the lib.rs in the rand crate.
     pub fn iter_eq<I, J>(i: I, j: J) -> bool
         where I: IntoIterator, J: IntoIterator<Item = I::Item>, I::Item: Eq {
 
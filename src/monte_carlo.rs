@@ -0,0 +1,223 @@
+// Written by Alexander Stocko <as@coder.gg>
+//
+// To the extent possible under law, the author has dedicated all copyright
+// and related and neighboring rights to this software to the public domain
+// worldwide. This software is distributed without any warranty.
+//
+// See <LICENSE or http://creativecommons.org/publicdomain/zero/1.0/>
+
+//! Reproducible Monte Carlo estimation.
+//!
+//! The samples are split into tasks of `TASK_SIZE`. Task `i` draws from
+//! the base generator jumped `i` times, and the per-task statistics are
+//! merged in task order. The estimate is therefore bit-identical for any
+//! number of worker threads. With the `rayon` feature tasks run in
+//! parallel; without it they run sequentially, with the same result.
+//!
+//! ```rust
+//! use xorshift::{Rng, Xoroshiro128};
+//! use xorshift::monte_carlo;
+//!
+//! // Estimate pi from the fraction of points inside the unit circle
+//! let base = Xoroshiro128::from_seed_conditioned(&[42]);
+//! let estimate = monte_carlo::run(1_000_000, base, |rng| {
+//!     let (x, y) = (rng.next_f64(), rng.next_f64());
+//!     if x * x + y * y < 1.0 { 4.0 } else { 0.0 }
+//! });
+//! let (low, high) = estimate.confidence_interval(3.0);
+//! assert!(low < std::f64::consts::PI && std::f64::consts::PI < high);
+//! ```
+
+use rand::Rng;
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+use RngJump;
+
+/// Number of samples drawn from each jumped stream.
+pub const TASK_SIZE: u64 = 1 << 14;
+
+// Number of task generators prepared at a time, bounding memory use.
+const BATCH: usize = 1024;
+
+/// `Send` with the `rayon` feature, and implemented by every type without
+/// it, so that `run` only needs `Send` generators when tasks are parallel.
+#[cfg(feature = "rayon")]
+pub trait MaybeSend: Send {}
+#[cfg(feature = "rayon")]
+impl<T: Send> MaybeSend for T {}
+
+/// `Send` with the `rayon` feature, and implemented by every type without
+/// it, so that `run` only needs `Send` generators when tasks are parallel.
+#[cfg(not(feature = "rayon"))]
+pub trait MaybeSend {}
+#[cfg(not(feature = "rayon"))]
+impl<T> MaybeSend for T {}
+
+/// `Sync` with the `rayon` feature, and implemented by every type without
+/// it, so that `run` only needs `Sync` closures when tasks are parallel.
+#[cfg(feature = "rayon")]
+pub trait MaybeSync: Sync {}
+#[cfg(feature = "rayon")]
+impl<T: Sync> MaybeSync for T {}
+
+/// `Sync` with the `rayon` feature, and implemented by every type without
+/// it, so that `run` only needs `Sync` closures when tasks are parallel.
+#[cfg(not(feature = "rayon"))]
+pub trait MaybeSync {}
+#[cfg(not(feature = "rayon"))]
+impl<T> MaybeSync for T {}
+
+/// The result of a Monte Carlo run.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Estimate {
+    /// Number of samples.
+    pub samples: u64,
+    /// Sample mean.
+    pub mean: f64,
+    /// Unbiased sample variance.
+    pub variance: f64,
+}
+
+impl Estimate {
+    /// Standard error of the mean.
+    pub fn std_error(&self) -> f64 {
+        (self.variance / self.samples as f64).sqrt()
+    }
+
+    /// Normal-approximation confidence interval for the mean, `z` standard
+    /// errors either side. Use `z = 1.96` for 95% confidence.
+    pub fn confidence_interval(&self, z: f64) -> (f64, f64) {
+        let half_width = z * self.std_error();
+        (self.mean - half_width, self.mean + half_width)
+    }
+}
+
+// Count, mean and sum of squared deviations, accumulated with Welford's
+// method and merged with the formula of Chan et al.
+#[derive(Copy, Clone, Default)]
+struct Moments {
+    n: u64,
+    mean: f64,
+    m2: f64,
+}
+
+impl Moments {
+    #[inline]
+    fn push(&mut self, x: f64) {
+        self.n += 1;
+        let delta = x - self.mean;
+        self.mean += delta / self.n as f64;
+        self.m2 += delta * (x - self.mean);
+    }
+
+    fn merge(self, other: Moments) -> Moments {
+        if self.n == 0 {
+            return other;
+        }
+        let n = self.n + other.n;
+        let delta = other.mean - self.mean;
+        Moments {
+            n,
+            mean: self.mean + delta * (other.n as f64 / n as f64),
+            m2: self.m2 + other.m2 +
+                delta * delta * (self.n as f64 * other.n as f64 / n as f64),
+        }
+    }
+}
+
+/// Estimate the mean of `f` over `n_samples` calls.
+///
+/// `f` receives the generator for the current task and returns one sample.
+/// It may draw any number of values. Panics if `n_samples` is less than 2.
+pub fn run<R, F>(n_samples: u64, base: R, f: F) -> Estimate
+    where R: Rng + RngJump + Clone + MaybeSend, F: Fn(&mut R) -> f64 + MaybeSync
+{
+    assert!(n_samples >= 2, "monte_carlo::run called with fewer than 2 samples.");
+
+    let task = |(start, mut rng): (u64, R)| {
+        let mut moments = Moments::default();
+        for _ in start..n_samples.min(start + TASK_SIZE) {
+            moments.push(f(&mut rng));
+        }
+        moments
+    };
+
+    let mut total = Moments::default();
+    let mut rng = base;
+    let mut start = 0;
+    while start < n_samples {
+        let mut tasks = Vec::with_capacity(BATCH);
+        while tasks.len() < BATCH && start < n_samples {
+            tasks.push((start, rng.clone()));
+            rng.jump(1);
+            start += TASK_SIZE;
+        }
+
+        #[cfg(feature = "rayon")]
+        let partials = tasks.into_par_iter().map(task).collect::<Vec<Moments>>();
+        #[cfg(not(feature = "rayon"))]
+        let partials = tasks.into_iter().map(task).collect::<Vec<Moments>>();
+
+        total = partials.into_iter().fold(total, Moments::merge);
+    }
+
+    Estimate {
+        samples: total.n,
+        mean: total.mean,
+        variance: total.m2 / (total.n - 1) as f64,
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use xoroshiro128::Xoroshiro128;
+    use xorshift1024::Xorshift1024;
+    use super::{run, TASK_SIZE};
+    #[test]
+    fn test() {
+        // Pinned so that estimates stay stable across releases, and are the
+        // same with and without the rayon feature
+        let base = Xoroshiro128::from_seed_conditioned(&[1, 2]);
+        let estimate = run(10 * TASK_SIZE + 7, base, |rng| rng.next_f64());
+        assert_eq!(estimate.samples, 10 * TASK_SIZE + 7);
+        assert_eq!((estimate.mean.to_bits(), estimate.variance.to_bits()),
+                   (4602674351868953179, 4590665336026992924));
+
+        let (low, high) = estimate.confidence_interval(3.0);
+        assert!(low < 0.5 && 0.5 < high);
+        assert!((estimate.variance - 1.0 / 12.0).abs() < 0.001);
+
+        let base = Xorshift1024::from_seed_conditioned(&[1, 2]);
+        let estimate = run(100000, base, |rng| rng.next_f64() * 2.0);
+        let (low, high) = estimate.confidence_interval(3.0);
+        assert!(low < 1.0 && 1.0 < high);
+    }
+
+    #[test]
+    #[cfg(not(feature = "rayon"))]
+    fn test_unsync() {
+        use std::cell::Cell;
+
+        // Without rayon the closure need not be Sync
+        let calls = Cell::new(0);
+        run(100, Xoroshiro128::from_seed_conditioned(&[1, 2]), |rng| {
+            calls.set(calls.get() + 1);
+            rng.next_f64()
+        });
+        assert_eq!(calls.get(), 100);
+    }
+
+    #[test]
+    #[cfg(feature = "rayon")]
+    fn test_threads() {
+        use rayon::ThreadPoolBuilder;
+
+        let base = Xorshift1024::from_seed_conditioned(&[3]);
+        let estimates = [1, 2, 7].iter().map(|threads| {
+            let pool = ThreadPoolBuilder::new().num_threads(*threads).build().unwrap();
+            pool.install(|| run(40 * TASK_SIZE + 1, base, |rng| rng.next_f64().ln()))
+        }).collect::<Vec<_>>();
+        assert!(estimates.iter().all(|e| *e == estimates[0]));
+    }
+}
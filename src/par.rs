@@ -0,0 +1,326 @@
+// Written by Alexander Stocko <as@coder.gg>
+//
+// To the extent possible under law, the author has dedicated all copyright
+// and related and neighboring rights to this software to the public domain
+// worldwide. This software is distributed without any warranty.
+//
+// See <LICENSE or http://creativecommons.org/publicdomain/zero/1.0/>
+
+//! Deterministic parallel generation with rayon.
+//!
+//! Output is split into chunks of `CHUNK` values. Chunk `i` is produced by
+//! the base generator jumped `i` times, so element `p` is always output
+//! `p % CHUNK` of the stream for chunk `p / CHUNK`, however many threads
+//! take part and however rayon schedules the work.
+//!
+//! ```rust
+//! use xorshift::{Xorshift1024, par_fill_u64};
+//!
+//! let base = Xorshift1024::from_seed_conditioned(&[42]);
+//! let mut buf = vec![0; 100_000];
+//! par_fill_u64(&mut buf, base);
+//! ```
+
+use rand::Rng;
+use rayon::prelude::*;
+use rayon::iter::plumbing::{bridge, Consumer, Producer, ProducerCallback, UnindexedConsumer};
+use RngJump;
+
+/// Number of values produced from each jumped stream.
+pub const CHUNK: usize = 1 << 16;
+
+// Generators for the first `n` chunks, each one jump beyond the last.
+fn chunk_rngs<R: RngJump + Clone>(base: R, n: usize) -> Vec<R> {
+    let mut rngs = Vec::with_capacity(n);
+    let mut rng = base;
+    for i in 0..n {
+        if i != 0 {
+            rng.jump(1);
+        }
+        rngs.push(rng.clone());
+    }
+    rngs
+}
+
+/// Fill `dest` with random `u64`s in parallel.
+///
+/// The result depends only on `base` and the length of `dest`, and equals
+/// `ParRngIter::new(base, dest.len())` collected in order.
+pub fn par_fill_u64<R>(dest: &mut [u64], base: R)
+    where R: Rng + RngJump + Clone + Send
+{
+    let rngs = chunk_rngs(base, dest.len().div_ceil(CHUNK));
+    dest.par_chunks_mut(CHUNK).zip(rngs).for_each(|(chunk, mut rng)| {
+        for x in chunk {
+            *x = rng.next_u64();
+        }
+    });
+}
+
+/// An indexed parallel iterator over `len` random `u64`s.
+///
+/// Yields the same values as `par_fill_u64`, so it can be zipped, mapped
+/// and collected with reproducible results.
+///
+/// Iterating in reverse, as with `rev`, regenerates each value from the
+/// start of its chunk, and costs O(`CHUNK`) per item.
+///
+/// ```rust
+/// extern crate rayon;
+/// extern crate xorshift;
+///
+/// use rayon::prelude::*;
+/// use xorshift::{ParRngIter, Xoroshiro128};
+///
+/// fn main() {
+///     let base = Xoroshiro128::from_seed_conditioned(&[42]);
+///     let sum: u64 = ParRngIter::new(base, 1000).map(|x| x >> 60).sum();
+///     assert!(sum > 0);
+/// }
+/// ```
+#[derive(Clone)]
+pub struct ParRngIter<R> {
+    rngs: Vec<R>,
+    len: usize,
+}
+
+impl<R: RngJump + Clone> ParRngIter<R> {
+    /// Create an iterator over `len` values derived from `base`.
+    ///
+    /// Jumps `base` once per chunk up front; the jumps themselves are
+    /// sequential.
+    pub fn new(base: R, len: usize) -> ParRngIter<R> {
+        ParRngIter {
+            rngs: chunk_rngs(base, len.div_ceil(CHUNK)),
+            len,
+        }
+    }
+}
+
+impl<R: Rng + Clone + Send> ParallelIterator for ParRngIter<R> {
+    type Item = u64;
+
+    fn drive_unindexed<C: UnindexedConsumer<u64>>(self, consumer: C) -> C::Result {
+        bridge(self, consumer)
+    }
+
+    fn opt_len(&self) -> Option<usize> {
+        Some(self.len)
+    }
+}
+
+impl<R: Rng + Clone + Send> IndexedParallelIterator for ParRngIter<R> {
+    fn len(&self) -> usize {
+        self.len
+    }
+
+    fn drive<C: Consumer<u64>>(self, consumer: C) -> C::Result {
+        bridge(self, consumer)
+    }
+
+    fn with_producer<CB: ProducerCallback<u64>>(self, callback: CB) -> CB::Output {
+        callback.callback(RngProducer {
+            rngs: self.rngs,
+            first: 0,
+            start: 0,
+            end: self.len,
+        })
+    }
+}
+
+// Positions start..end, owning the generators of the chunks they cover.
+// `rngs[0]` is the generator for chunk `first`.
+struct RngProducer<R> {
+    rngs: Vec<R>,
+    first: usize,
+    start: usize,
+    end: usize,
+}
+
+impl<R: Rng + Clone + Send> Producer for RngProducer<R> {
+    type Item = u64;
+    type IntoIter = RngIter<R>;
+
+    fn into_iter(self) -> RngIter<R> {
+        RngIter {
+            rngs: self.rngs,
+            first: self.first,
+            rng: None,
+            start: self.start,
+            end: self.end,
+        }
+    }
+
+    fn min_len(&self) -> usize {
+        CHUNK
+    }
+
+    // A chunk that straddles the split point is cloned into both halves.
+    fn split_at(mut self, index: usize) -> (Self, Self) {
+        let mid = self.start + index;
+        let rngs = self.rngs.split_off(mid / CHUNK - self.first);
+        if mid % CHUNK != 0 {
+            self.rngs.push(rngs[0].clone());
+        }
+        let right = RngProducer {
+            rngs,
+            first: mid / CHUNK,
+            start: mid,
+            end: self.end,
+        };
+        (RngProducer { end: mid, ..self }, right)
+    }
+}
+
+// Sequential iterator over positions start..end. The generator for the
+// current position is created on first use by advancing its chunk's stream.
+struct RngIter<R> {
+    rngs: Vec<R>,
+    first: usize,
+    rng: Option<R>,
+    start: usize,
+    end: usize,
+}
+
+impl<R: Rng + Clone> RngIter<R> {
+    // Generator positioned to produce the value at `pos`.
+    fn rng_at(&self, pos: usize) -> R {
+        let mut rng = self.rngs[pos / CHUNK - self.first].clone();
+        for _ in 0..pos % CHUNK {
+            rng.next_u64();
+        }
+        rng
+    }
+}
+
+impl<R: Rng + Clone> Iterator for RngIter<R> {
+    type Item = u64;
+
+    #[inline]
+    fn next(&mut self) -> Option<u64> {
+        if self.start == self.end {
+            return None;
+        }
+        let mut rng = match self.rng.take() {
+            Some(rng) if self.start % CHUNK != 0 => rng,
+            _ => self.rng_at(self.start),
+        };
+        let x = rng.next_u64();
+        self.rng = Some(rng);
+        self.start += 1;
+        Some(x)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.end - self.start, Some(self.end - self.start))
+    }
+}
+
+impl<R: Rng + Clone> ExactSizeIterator for RngIter<R> {}
+
+// Rayon only iterates backwards for reversed iterators. Each value is
+// regenerated from the start of its chunk, which is slow but correct.
+impl<R: Rng + Clone> DoubleEndedIterator for RngIter<R> {
+    fn next_back(&mut self) -> Option<u64> {
+        if self.start == self.end {
+            return None;
+        }
+        self.end -= 1;
+        Some(self.rng_at(self.end).next_u64())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+    use rand::Rng;
+    use rayon::prelude::*;
+    use rayon::ThreadPoolBuilder;
+    use xoroshiro128::Xoroshiro128;
+    use xorshift1024::Xorshift1024;
+    use RngJump;
+    use super::{par_fill_u64, ParRngIter, CHUNK};
+
+    // A generator that can be sent between threads but not shared.
+    #[derive(Clone)]
+    struct Unshared(Xoroshiro128, Cell<()>);
+
+    impl Rng for Unshared {
+        fn next_u32(&mut self) -> u32 {
+            self.0.next_u32()
+        }
+
+        fn next_u64(&mut self) -> u64 {
+            self.0.next_u64()
+        }
+    }
+
+    impl RngJump for Unshared {
+        fn jump(&mut self, count: usize) {
+            self.0.jump(count)
+        }
+    }
+
+    fn in_pool<T: Send, F: FnOnce() -> T + Send>(threads: usize, f: F) -> T {
+        ThreadPoolBuilder::new().num_threads(threads).build().unwrap().install(f)
+    }
+
+    #[test]
+    fn test() {
+        let base = Xorshift1024::from_seed_conditioned(&[1, 2]);
+        let len = 3 * CHUNK + 123;
+
+        // Element p is output p % CHUNK after p / CHUNK jumps
+        let mut expected = Vec::with_capacity(len);
+        let mut chunk_rng = base;
+        while expected.len() < len {
+            let mut rng = chunk_rng;
+            for _ in 0..CHUNK.min(len - expected.len()) {
+                expected.push(rng.next_u64());
+            }
+            chunk_rng.jump(1);
+        }
+
+        for threads in [1, 3, 8].iter().cloned() {
+            let mut buf = vec![0; len];
+            in_pool(threads, || par_fill_u64(&mut buf, base));
+            assert!(buf == expected);
+
+            let collected = in_pool(threads, || {
+                ParRngIter::new(base, len).collect::<Vec<u64>>()
+            });
+            assert!(collected == expected);
+        }
+
+        // Splitting mid-chunk and reversing still give the same values
+        let iter = ParRngIter::new(base, len);
+        let zipped = iter.clone().zip(0..len).with_min_len(1000)
+            .map(|(x, i)| (x == expected[i]) as usize).sum::<usize>();
+        assert_eq!(zipped, len);
+        let tail = iter.skip(len - 10).rev().collect::<Vec<u64>>();
+        assert!(tail.iter().eq(expected[len - 10..].iter().rev()));
+    }
+
+    #[test]
+    fn test_xoroshiro() {
+        let base = Xoroshiro128::from_seed_conditioned(&[1, 2]);
+        let mut a = vec![0; 2 * CHUNK + 1];
+        let mut b = vec![0; 2 * CHUNK + 1];
+        in_pool(1, || par_fill_u64(&mut a, base));
+        in_pool(4, || par_fill_u64(&mut b, base));
+        assert!(a == b);
+        let mut jumped = base;
+        jumped.jump(2);
+        assert_eq!(a[2 * CHUNK], jumped.next_u64());
+
+        let unshared = Unshared(base, Cell::new(()));
+        let (rng, dest) = (unshared.clone(), &mut b);
+        in_pool(4, move || par_fill_u64(dest, rng));
+        assert!(a == b);
+        let len = a.len();
+        let collected = in_pool(4, move || {
+            ParRngIter::new(unshared, len).collect::<Vec<u64>>()
+        });
+        assert!(collected == a);
+    }
+}
@@ -0,0 +1,220 @@
+// Written by Alexander Stocko <as@coder.gg>
+//
+// To the extent possible under law, the author has dedicated all copyright
+// and related and neighboring rights to this software to the public domain
+// worldwide. This software is distributed without any warranty.
+//
+// See <LICENSE or http://creativecommons.org/publicdomain/zero/1.0/>
+
+//! Pseudorandom permutations of `0..n` that are never materialized.
+
+use hash::mix64;
+use splitmix64::SplitMix64;
+
+const ROUNDS: usize = 8;
+
+/// A pseudorandom permutation of `0..n`, evaluated lazily in O(1) space.
+///
+/// The permutation is a balanced Feistel network over the smallest domain
+/// of `2^(2h)` values that holds `n`, with the splitmix64 output function
+/// as the round function. Values that land outside `0..n` are encrypted
+/// again until they fall inside (cycle walking). As the domain is less
+/// than `4n`, each lookup takes fewer than four network evaluations on
+/// average.
+///
+/// ```rust
+/// use xorshift::RandomPermutation;
+///
+/// let perm = RandomPermutation::new(1_000_000_000, 42);
+/// let j = perm.get(123_456_789);
+/// assert!(j < 1_000_000_000);
+/// assert_eq!(perm.inverse(j), 123_456_789);
+/// ```
+#[derive(Copy, Clone, Debug)]
+pub struct RandomPermutation {
+    n: u64,
+    half_bits: u32,
+    keys: [u64; ROUNDS],
+}
+
+impl RandomPermutation {
+    /// Create the permutation of `0..n` selected by `seed`.
+    pub const fn new(n: u64, seed: u64) -> RandomPermutation {
+        let bits = 64 - n.saturating_sub(1).leading_zeros();
+        let mut rng = SplitMix64::new(seed);
+        let mut keys = [0; ROUNDS];
+        let mut i = 0;
+        while i < ROUNDS {
+            keys[i] = rng.next_u64();
+            i += 1;
+        }
+
+        RandomPermutation {
+            n,
+            half_bits: if bits < 2 { 1 } else { bits.div_ceil(2) },
+            keys,
+        }
+    }
+
+    /// Number of elements permuted.
+    pub const fn len(&self) -> u64 {
+        self.n
+    }
+
+    /// Whether the permutation is of the empty range.
+    pub const fn is_empty(&self) -> bool {
+        self.n == 0
+    }
+
+    /// Return the element at position `i`.
+    ///
+    /// Panics if `i` is not less than `n`.
+    pub fn get(&self, i: u64) -> u64 {
+        assert!(i < self.n, "RandomPermutation::get called with i >= n.");
+        let mut x = self.encrypt(i);
+        while x >= self.n {
+            x = self.encrypt(x);
+        }
+        x
+    }
+
+    /// Return the position of element `j`, so that `get(inverse(j)) == j`.
+    ///
+    /// Panics if `j` is not less than `n`.
+    pub fn inverse(&self, j: u64) -> u64 {
+        assert!(j < self.n, "RandomPermutation::inverse called with j >= n.");
+        let mut x = self.decrypt(j);
+        while x >= self.n {
+            x = self.decrypt(x);
+        }
+        x
+    }
+
+    /// Iterate over the permuted elements in position order.
+    pub fn iter(&self) -> Iter {
+        Iter {
+            perm: *self,
+            pos: 0,
+        }
+    }
+
+    #[inline]
+    fn round(&self, x: u64, key: u64) -> u64 {
+        mix64(x ^ key) & self.mask()
+    }
+
+    #[inline]
+    fn mask(&self) -> u64 {
+        (1 << self.half_bits) - 1
+    }
+
+    fn encrypt(&self, x: u64) -> u64 {
+        let (mut left, mut right) = (x >> self.half_bits, x & self.mask());
+        for key in self.keys.iter() {
+            let t = left ^ self.round(right, *key);
+            left = right;
+            right = t;
+        }
+        left << self.half_bits | right
+    }
+
+    fn decrypt(&self, x: u64) -> u64 {
+        let (mut left, mut right) = (x >> self.half_bits, x & self.mask());
+        for key in self.keys.iter().rev() {
+            let t = right ^ self.round(left, *key);
+            right = left;
+            left = t;
+        }
+        left << self.half_bits | right
+    }
+}
+
+impl IntoIterator for &RandomPermutation {
+    type Item = u64;
+    type IntoIter = Iter;
+
+    fn into_iter(self) -> Iter {
+        self.iter()
+    }
+}
+
+/// Iterator over the elements of a `RandomPermutation`.
+#[derive(Clone, Debug)]
+pub struct Iter {
+    perm: RandomPermutation,
+    pos: u64,
+}
+
+impl Iterator for Iter {
+    type Item = u64;
+
+    #[inline]
+    fn next(&mut self) -> Option<u64> {
+        if self.pos == self.perm.n {
+            return None;
+        }
+        self.pos += 1;
+        Some(self.perm.get(self.pos - 1))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.perm.n - self.pos;
+        if remaining > usize::MAX as u64 {
+            (usize::MAX, None)
+        } else {
+            (remaining as usize, Some(remaining as usize))
+        }
+    }
+
+    fn nth(&mut self, n: usize) -> Option<u64> {
+        self.pos = self.pos.saturating_add(n as u64).min(self.perm.n);
+        self.next()
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::RandomPermutation;
+    #[test]
+    fn test() {
+        // Pinned so that permutations stay stable across releases
+        let perm = RandomPermutation::new(10, 1477776061723855037);
+        assert_eq!(perm.iter().collect::<Vec<u64>>(), vec![2, 4, 9, 1, 5, 0, 6, 3, 7, 8]);
+
+        for n in [0, 1, 2, 3, 5, 16, 17, 1000, 65537].iter().cloned() {
+            let perm = RandomPermutation::new(n, n ^ 0xdead_beef);
+            let mut seen = vec![false; n as usize];
+            for (i, j) in perm.iter().enumerate() {
+                assert!(!seen[j as usize]);
+                seen[j as usize] = true;
+                assert_eq!(perm.inverse(j), i as u64);
+            }
+            assert!(seen.iter().all(|s| *s));
+            assert_eq!(perm.iter().count() as u64, n);
+        }
+
+        let perm = RandomPermutation::new(u64::MAX, 7);
+        for i in [0, 1, u64::MAX / 3, u64::MAX - 1].iter().cloned() {
+            assert_eq!(perm.inverse(perm.get(i)), i);
+        }
+        let mut iter = perm.iter();
+        assert_eq!(iter.nth(5), Some(perm.get(5)));
+    }
+
+    #[test]
+    fn test_seed() {
+        // Different seeds give different orders, and the order is not close
+        // to the identity
+        let a = RandomPermutation::new(1000, 1).iter().collect::<Vec<u64>>();
+        let b = RandomPermutation::new(1000, 2).iter().collect::<Vec<u64>>();
+        assert!(a != b);
+        assert!(a.iter().enumerate().filter(|&(i, j)| i as u64 == *j).count() < 10);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_out_of_range() {
+        RandomPermutation::new(10, 1).get(10);
+    }
+}
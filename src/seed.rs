@@ -0,0 +1,119 @@
+// Written by Alexander Stocko <as@coder.gg>
+//
+// To the extent possible under law, the author has dedicated all copyright
+// and related and neighboring rights to this software to the public domain
+// worldwide. This software is distributed without any warranty.
+//
+// See <LICENSE or http://creativecommons.org/publicdomain/zero/1.0/>
+
+//! Seed conditioning helpers shared by the generators.
+
+use splitmix64::SplitMix64;
+
+// Domain tags absorbed first, so conditioned seeds and labels never share
+// an absorbed word sequence. These are "seedcond" and "seedlabl" in ASCII.
+const CONDITION_TAG: u64 = 0x7365_6564_636f_6e64;
+const LABEL_TAG: u64 = 0x7365_6564_6c61_626c;
+
+/// Fill `state` from raw seed material by absorbing each seed word into a
+/// splitmix64 generator and using its output.
+///
+/// Seeds of any non-zero length are accepted. Words beyond the state size
+/// are folded back into the state. The seed length is absorbed before the
+/// seed, so seeds that differ only by trailing zero words give different
+/// states.
+pub fn condition(name: &str, seed: &[u64], state: &mut [u64]) {
+    if seed.is_empty() {
+        panic!("{} seed needs at least one u64 for conditioned seeding.", name);
+    }
+    absorb(Some(CONDITION_TAG).into_iter()
+               .chain(Some(seed.len() as u64))
+               .chain(seed.iter().cloned()),
+           state);
+}
+
+/// Fill `state` from a master seed and a stream label.
+///
+/// The label bytes are packed little-endian into words after the master
+/// seed and label length, so the result does not depend on the platform.
+pub fn label(master: u64, label: &str, state: &mut [u64]) {
+    let bytes = label.as_bytes();
+    let words = bytes.chunks(8).map(|chunk| {
+        chunk.iter().rev().fold(0, |word, b| word << 8 | *b as u64)
+    });
+    absorb(Some(LABEL_TAG).into_iter()
+               .chain(Some(master))
+               .chain(Some(bytes.len() as u64))
+               .chain(words),
+           state);
+}
+
+fn absorb<I: Iterator<Item = u64>>(words: I, state: &mut [u64]) {
+    let mut sm = SplitMix64::new(0);
+    for word in state.iter_mut() {
+        *word = 0;
+    }
+
+    let n = state.len();
+    let mut count = 0;
+    for word in words {
+        sm.absorb(word);
+        state[count % n] ^= sm.next_u64();
+        count += 1;
+    }
+    for word in state.iter_mut().skip(count) {
+        *word = sm.next_u64();
+    }
+}
+
+/// Returns true if the seed words are all identical or have a very low
+/// Hamming weight.
+#[cfg(feature = "std")]
+pub fn is_weak(seed: &[u64]) -> bool {
+    if seed.len() > 1 && seed.iter().all(|s| *s == seed[0]) {
+        return true;
+    }
+
+    let ones: usize = seed.iter().map(|s| s.count_ones() as usize).sum();
+    ones < 8 * seed.len()
+}
+
+/// Print a warning in debug builds when a raw seed looks weak.
+#[cfg(feature = "std")]
+#[inline]
+pub fn check(name: &str, seed: &[u64]) {
+    if cfg!(debug_assertions) && is_weak(seed) {
+        eprintln!("warning: {} seeded with low-entropy state {:?}; consider \
+                   from_seed_conditioned",
+                  name,
+                  seed);
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::{condition, is_weak};
+    #[test]
+    fn test() {
+        assert!(is_weak(&[42, 42]));
+        assert!(is_weak(&[1, 0, 0, 0]));
+        assert!(!is_weak(&[0x9E3779B97F4A7C15, 0xBF58476D1CE4E5B9]));
+
+        let mut a = [0; 16];
+        let mut b = [0; 16];
+        condition("test", &[7; 16], &mut a);
+        condition("test", &[7; 17], &mut b);
+        assert!(a != b);
+        assert!(!is_weak(&a));
+
+        let mut c = [0; 2];
+        let mut d = [0; 2];
+        condition("test", &[7], &mut c);
+        condition("test", &[7, 0], &mut d);
+        assert!(c != d);
+        for (i, x) in a.iter().enumerate() {
+            assert!(a[i + 1..].iter().all(|y| x != y));
+        }
+    }
+}
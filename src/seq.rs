@@ -0,0 +1,161 @@
+// Written by Alexander Stocko <as@coder.gg>
+//
+// To the extent possible under law, the author has dedicated all copyright
+// and related and neighboring rights to this software to the public domain
+// worldwide. This software is distributed without any warranty.
+//
+// See <LICENSE or http://creativecommons.org/publicdomain/zero/1.0/>
+
+//! Shuffling and sampling without replacement.
+//!
+//! `shuffle` and `sample_indices` draw every index with `below_u64`, never
+//! through `usize` ranges, so a given seed produces the same result on 32
+//! and 64-bit targets. `reservoir` computes its skips with the platform's
+//! `exp` and `ln`, so its choices are stable across releases but can
+//! differ between math libraries.
+
+use std::collections::HashSet;
+use rand::Rng;
+use float::f64_open_from_u64;
+use hash::BuildMix64Hasher;
+use uniform::below_u64;
+
+/// Shuffle `values` in place with the Fisher-Yates algorithm.
+///
+/// Swaps from the back, drawing one bounded integer per element.
+pub fn shuffle<T, R: Rng + ?Sized>(rng: &mut R, values: &mut [T]) {
+    for i in (1..values.len()).rev() {
+        let j = below_u64(rng, i as u64 + 1) as usize;
+        values.swap(i, j);
+    }
+}
+
+/// Return `k` distinct indices from `0..n`, chosen with Floyd's algorithm.
+///
+/// Takes exactly `k` bounded draws. The indices are returned in the order
+/// they were selected, which is not itself uniformly random; shuffle the
+/// result if that is needed. Panics if `k > n`.
+pub fn sample_indices<R: Rng + ?Sized>(rng: &mut R, n: usize, k: usize) -> Vec<usize> {
+    assert!(k <= n, "sample_indices called with k > n.");
+
+    let mut selected = HashSet::with_capacity_and_hasher(k, BuildMix64Hasher::default());
+    let mut indices = Vec::with_capacity(k);
+    for j in (n - k) as u64..n as u64 {
+        let t = below_u64(rng, j + 1);
+        let index = if selected.insert(t) { t } else {
+            selected.insert(j);
+            j
+        };
+        indices.push(index as usize);
+    }
+    indices
+}
+
+/// Choose `k` items uniformly from an iterator of unknown length, using
+/// Li's Algorithm L [1].
+///
+/// The iterator is consumed, but only about `k (1 + ln(n / k))` items
+/// require random draws; the rest are skipped over. Returns every item if
+/// there are at most `k`, in iteration order.
+///
+/// [1]: Kim-Hung Li, [*Reservoir-Sampling Algorithms of Time Complexity
+/// O(n(1 + log(N/n)))*](https://doi.org/10.1145/198429.198435)
+pub fn reservoir<I, R>(rng: &mut R, iter: I, k: usize) -> Vec<I::Item>
+    where I: IntoIterator, R: Rng + ?Sized
+{
+    let mut iter = iter.into_iter();
+    let mut sample = iter.by_ref().take(k).collect::<Vec<I::Item>>();
+    if sample.len() < k || k == 0 {
+        return sample;
+    }
+
+    let k_f = k as f64;
+    let mut w = (f64_open_from_u64(rng.next_u64()).ln() / k_f).exp();
+    loop {
+        let skip = (f64_open_from_u64(rng.next_u64()).ln() / (-w).ln_1p()).floor();
+        match nth(&mut iter, skip as u64) {
+            Some(item) => {
+                sample[below_u64(rng, k as u64) as usize] = item;
+                w *= (f64_open_from_u64(rng.next_u64()).ln() / k_f).exp();
+            }
+            None => return sample,
+        }
+    }
+}
+
+// Iterator::nth with a u64 count.
+fn nth<I: Iterator>(iter: &mut I, mut n: u64) -> Option<I::Item> {
+    while n > usize::MAX as u64 {
+        iter.nth(usize::MAX - 1)?;
+        n -= usize::MAX as u64;
+    }
+    iter.nth(n as usize)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use test::{pinned, xoroshiro};
+    use super::{reservoir, sample_indices, shuffle};
+    #[test]
+    fn test_shuffle() {
+        let mut rng = pinned();
+        let mut vals = (0..10).collect::<Vec<u32>>();
+        shuffle(&mut rng, &mut vals);
+        assert_eq!(vals, vec![0, 8, 2, 6, 7, 4, 3, 5, 9, 1]);
+
+        // All 6 orderings of 3 elements are equally likely
+        let mut rng = xoroshiro();
+        let mut counts = [0; 6];
+        for _ in 0..60000 {
+            let mut vals = [0, 1, 2];
+            shuffle(&mut rng, &mut vals);
+            counts[vals[0] * 2 + (vals[1] > vals[2]) as usize] += 1;
+        }
+        assert!(counts.iter().all(|n| *n > 9600 && *n < 10400));
+
+        shuffle(&mut rng, &mut [0u8; 0]);
+    }
+
+    #[test]
+    fn test_sample_indices() {
+        let mut rng = pinned();
+        assert_eq!(sample_indices(&mut rng, 100, 8), vec![10, 15, 69, 44, 79, 90, 87, 71]);
+
+        let mut rng = xoroshiro();
+        let mut counts = [0; 10];
+        for _ in 0..10000 {
+            let mut indices = sample_indices(&mut rng, 10, 3);
+            indices.sort();
+            indices.dedup();
+            assert_eq!(indices.len(), 3);
+            for i in indices {
+                counts[i] += 1;
+            }
+        }
+        assert!(counts.iter().all(|n| *n > 2800 && *n < 3200));
+
+        let mut all = sample_indices(&mut rng, 5, 5);
+        all.sort();
+        assert_eq!(all, vec![0, 1, 2, 3, 4]);
+        assert!(sample_indices(&mut rng, 5, 0).is_empty());
+    }
+
+    #[test]
+    fn test_reservoir() {
+        let mut rng = pinned();
+        assert_eq!(reservoir(&mut rng, 0..1000000u64, 5),
+                   vec![620940, 840992, 250586, 806533, 745514]);
+        assert_eq!(reservoir(&mut rng, 0..3, 5), vec![0, 1, 2]);
+        assert!(reservoir(&mut rng, 0..3, 0).is_empty());
+
+        let mut rng = xoroshiro();
+        let mut counts = [0; 20];
+        for _ in 0..10000 {
+            for i in reservoir(&mut rng, 0..20, 4) {
+                counts[i] += 1;
+            }
+        }
+        assert!(counts.iter().all(|n| *n > 1800 && *n < 2200));
+    }
+}
@@ -8,9 +8,16 @@
 
 //! The `SplitMix64` random number generator.
 
-use std::num::Wrapping as w;
+#[cfg(feature = "std")]
 use rand::{Rand, Rng, SeedableRng};
 
+use RngInfo;
+#[cfg(feature = "getrandom")]
+use entropy::{self, EntropyError};
+use float;
+use hash::mix64;
+use seed;
+
 /// A random number generator that uses the splitmix64 algorithm [1].
 ///
 /// # Description
@@ -37,22 +44,104 @@ use rand::{Rand, Rng, SeedableRng};
 #[derive(Copy, Clone)]
 pub struct SplitMix64(u64);
 
+impl SplitMix64 {
+    /// Create a generator from a 64-bit seed.
+    pub const fn new(seed: u64) -> SplitMix64 {
+        SplitMix64(seed)
+    }
+
+    /// Create a generator for the stream named `label` under `master_seed`.
+    ///
+    /// Each label gives an independent stream that does not depend on the
+    /// order in which streams are created. The derivation is stable across
+    /// crate versions and platforms.
+    pub fn from_label(master_seed: u64, label: &str) -> SplitMix64 {
+        let mut state = [0; 1];
+        seed::label(master_seed, label, &mut state);
+        SplitMix64(state[0])
+    }
+
+    /// Create a generator seeded from operating system entropy.
+    #[cfg(feature = "getrandom")]
+    pub fn from_entropy() -> Result<SplitMix64, EntropyError> {
+        let mut state = [0; 1];
+        entropy::fill(&mut state)?;
+        Ok(SplitMix64(state[0]))
+    }
+
+    // Add a seed word to the generator state, used for seed conditioning.
+    #[inline]
+    pub(crate) fn absorb(&mut self, word: u64) {
+        self.0 = self.0.wrapping_add(word);
+    }
+
+    // The raw counter, from which the next output is derived.
+    #[inline]
+    pub(crate) const fn state(self) -> u64 {
+        self.0
+    }
+
+    /// Return the next random `u32`, taken from the upper half of the next
+    /// `u64`.
+    #[inline]
+    pub const fn next_u32(&mut self) -> u32 {
+        (self.next_u64() >> 32) as u32
+    }
+
+    /// Return the next random `u64`.
+    #[inline]
+    pub const fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E3779B97F4A7C15_u64);
+        mix64(self.0)
+    }
+
+    /// Return the next random `f64` in [0, 1), built from the upper 53 bits
+    /// of the next `u64`.
+    #[inline]
+    pub fn next_f64(&mut self) -> f64 {
+        float::f64_from_u64(self.next_u64())
+    }
+
+    /// Return the next random `f32` in [0, 1), built from the upper 24 bits
+    /// of the next `u64`.
+    #[inline]
+    pub fn next_f32(&mut self) -> f32 {
+        float::f32_from_u64(self.next_u64())
+    }
+
+    /// Return the generator advanced by `count` calls to `next_u64`.
+    ///
+    /// The state is a plain counter, so this takes constant time and is
+    /// usable in constant expressions.
+    pub const fn advanced(self, count: u64) -> SplitMix64 {
+        SplitMix64(self.0.wrapping_add(count.wrapping_mul(0x9E3779B97F4A7C15_u64)))
+    }
+}
+
+#[cfg(feature = "std")]
 impl Rng for SplitMix64 {
     #[inline]
     fn next_u32(&mut self) -> u32 {
-        self.next_u64() as u32
+        SplitMix64::next_u32(self)
     }
 
     #[inline]
     fn next_u64(&mut self) -> u64 {
-        let mut z = w(self.0) + w(0x9E3779B97F4A7C15_u64);
-        self.0 = z.0;
-        z = (z ^ (z >> 30)) * w(0xBF58476D1CE4E5B9_u64);
-        z = (z ^ (z >> 27)) * w(0x94D049BB133111EB_u64);
-        (z ^ (z >> 31)).0
+        SplitMix64::next_u64(self)
+    }
+
+    #[inline]
+    fn next_f32(&mut self) -> f32 {
+        SplitMix64::next_f32(self)
+    }
+
+    #[inline]
+    fn next_f64(&mut self) -> f64 {
+        SplitMix64::next_f64(self)
     }
 }
 
+#[cfg(feature = "std")]
 impl SeedableRng<u64> for SplitMix64 {
     fn reseed(&mut self, seed: u64) {
         self.0 = seed;
@@ -63,12 +152,19 @@ impl SeedableRng<u64> for SplitMix64 {
     }
 }
 
+#[cfg(feature = "std")]
 impl Rand for SplitMix64 {
     fn rand<R: Rng>(other: &mut R) -> SplitMix64 {
         SeedableRng::from_seed(other.gen())
     }
 }
 
+impl RngInfo for SplitMix64 {
+    const STATE_BITS: u32 = 64;
+    const PERIOD_LOG2: u32 = 64;
+    const JUMP_LOG2: Option<u32> = None;
+    const WEAK_LOW_BITS: u32 = 0;
+}
 
 #[cfg(test)]
 mod tests {
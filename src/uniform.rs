@@ -0,0 +1,166 @@
+// Written by Alexander Stocko <as@coder.gg>
+//
+// To the extent possible under law, the author has dedicated all copyright
+// and related and neighboring rights to this software to the public domain
+// worldwide. This software is distributed without any warranty.
+//
+// See <LICENSE or http://creativecommons.org/publicdomain/zero/1.0/>
+
+//! Unbiased bounded integers.
+//!
+//! Uses Lemire's nearly divisionless multiply-and-reject method [1]. The
+//! bound is multiplied by a random word and the high half of the product
+//! is the result; a division is only needed in the rare case that the low
+//! half falls in the biased region. All integer widths are sampled through
+//! 64 or 128-bit arithmetic, so results are identical on every platform.
+//!
+//! [1]: Daniel Lemire, [*Fast Random Integer Generation in an Interval*]
+//! (https://arxiv.org/abs/1805.10941)
+
+use rand::Rng;
+
+use bounded;
+
+/// Return a uniformly distributed integer in [0, n).
+///
+/// Panics if `n` is zero.
+#[inline]
+pub fn below_u64<R: Rng + ?Sized>(rng: &mut R, n: u64) -> u64 {
+    assert!(n != 0, "gen_below called with a zero bound.");
+    bounded::below(rng.next_u64(), n, || rng.next_u64())
+}
+
+// Full 256-bit product of two u128s, as (high, low).
+#[inline]
+fn wide_mul(a: u128, b: u128) -> (u128, u128) {
+    const MASK: u128 = u64::MAX as u128;
+    let (a_hi, a_lo) = (a >> 64, a & MASK);
+    let (b_hi, b_lo) = (b >> 64, b & MASK);
+
+    let lo_lo = a_lo * b_lo;
+    let hi_lo = a_hi * b_lo;
+    let lo_hi = a_lo * b_hi;
+    let hi_hi = a_hi * b_hi;
+
+    let mid = (lo_lo >> 64) + (hi_lo & MASK) + (lo_hi & MASK);
+    let low = (mid << 64) | (lo_lo & MASK);
+    let high = hi_hi + (hi_lo >> 64) + (lo_hi >> 64) + (mid >> 64);
+    (high, low)
+}
+
+#[inline]
+fn next_u128<R: Rng + ?Sized>(rng: &mut R) -> u128 {
+    let high = rng.next_u64() as u128;
+    high << 64 | rng.next_u64() as u128
+}
+
+/// Return a uniformly distributed integer in [0, n).
+///
+/// Each attempt consumes two `u64`s, the first forming the high half.
+/// Panics if `n` is zero.
+#[inline]
+pub fn below_u128<R: Rng + ?Sized>(rng: &mut R, n: u128) -> u128 {
+    assert!(n != 0, "gen_below called with a zero bound.");
+
+    let (mut high, mut low) = wide_mul(next_u128(rng), n);
+    if low < n {
+        let threshold = n.wrapping_neg() % n;
+        while low < threshold {
+            let m = wide_mul(next_u128(rng), n);
+            high = m.0;
+            low = m.1;
+        }
+    }
+    high
+}
+
+/// An integer type that can be sampled uniformly from a closed range.
+pub trait UniformInt: Copy + PartialOrd {
+    /// Return a uniformly distributed value in [low, high].
+    ///
+    /// Panics if `low` is greater than `high`.
+    fn sample_inclusive<R: Rng + ?Sized>(low: Self, high: Self, rng: &mut R) -> Self;
+}
+
+macro_rules! uniform_int_64 {
+    ($($ty:ty => $unsigned:ty),*) => {$(
+        impl UniformInt for $ty {
+            #[inline]
+            fn sample_inclusive<R: Rng + ?Sized>(low: $ty, high: $ty, rng: &mut R) -> $ty {
+                assert!(low <= high, "gen_range_inclusive called with low > high.");
+                let range = high.wrapping_sub(low) as $unsigned as u64;
+                let offset = match range.checked_add(1) {
+                    Some(n) => below_u64(rng, n),
+                    None => rng.next_u64(),
+                };
+                low.wrapping_add(offset as $unsigned as $ty)
+            }
+        }
+    )*}
+}
+
+uniform_int_64! {
+    u8 => u8, u16 => u16, u32 => u32, u64 => u64, usize => usize,
+    i8 => u8, i16 => u16, i32 => u32, i64 => u64, isize => usize
+}
+
+macro_rules! uniform_int_128 {
+    ($($ty:ty),*) => {$(
+        impl UniformInt for $ty {
+            #[inline]
+            fn sample_inclusive<R: Rng + ?Sized>(low: $ty, high: $ty, rng: &mut R) -> $ty {
+                assert!(low <= high, "gen_range_inclusive called with low > high.");
+                let range = high.wrapping_sub(low) as u128;
+                let offset = match range.checked_add(1) {
+                    Some(n) => below_u128(rng, n),
+                    None => next_u128(rng),
+                };
+                low.wrapping_add(offset as $ty)
+            }
+        }
+    )*}
+}
+
+uniform_int_128! { u128, i128 }
+
+
+#[cfg(test)]
+mod tests {
+    use test::pinned;
+    use super::{below_u128, below_u64, wide_mul, UniformInt};
+    #[test]
+    fn test() {
+        let mut rng = pinned();
+        let vals = (0..20).map(|_| below_u64(&mut rng, 6)).collect::<Vec<u64>>();
+        assert_eq!(vals,
+                   vec![0, 0, 4, 2, 4, 5, 5, 4, 3, 5, 1, 3, 3, 2, 1, 2, 1, 1, 2, 1]);
+
+        let mut rng = pinned();
+        let mut seen = [0; 13];
+        for _ in 0..10000 {
+            let x = i8::sample_inclusive(-6, 6, &mut rng);
+            seen[(x + 6) as usize] += 1;
+        }
+        assert!(seen.iter().all(|n| *n > 600 && *n < 900));
+
+        assert_eq!(u64::sample_inclusive(7, 7, &mut rng), 7);
+        let x = i64::sample_inclusive(i64::MIN, i64::MAX, &mut rng);
+        let y = i128::sample_inclusive(i128::MIN, i128::MAX, &mut rng);
+        assert!(x != 0 && y != 0);
+        let x = u128::sample_inclusive(1 << 100, (1 << 100) + 2, &mut rng);
+        assert!((1 << 100..=(1 << 100) + 2).contains(&x));
+        assert!(below_u128(&mut rng, 3) < 3);
+    }
+
+    #[test]
+    fn test_wide_mul() {
+        let mut rng = pinned();
+        assert_eq!(wide_mul(u128::MAX, u128::MAX), (u128::MAX - 1, 1));
+        for _ in 0..1000 {
+            let a = rng.next_u64() as u128;
+            let b = rng.next_u64() as u128;
+            assert_eq!(wide_mul(a, b), (0, a * b));
+            assert_eq!(wide_mul(a << 64, b), ((a * b) >> 64, (a * b) << 64));
+        }
+    }
+}
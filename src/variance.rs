@@ -0,0 +1,285 @@
+// Written by Alexander Stocko <as@coder.gg>
+//
+// To the extent possible under law, the author has dedicated all copyright
+// and related and neighboring rights to this software to the public domain
+// worldwide. This software is distributed without any warranty.
+//
+// See <LICENSE or http://creativecommons.org/publicdomain/zero/1.0/>
+
+//! Variance reduction with antithetic variates and common random numbers.
+
+use std::collections::HashMap;
+use rand::Rng;
+use RngJump;
+
+/// A generator that replays its outputs inverted, for antithetic variates.
+///
+/// Draws are made in pairs of passes. The first pass returns the wrapped
+/// generator's outputs unchanged. `antithetic` rewinds to the start of the
+/// pair, after which integers are returned as `!x` and floats as `1 - u`
+/// (so in (0, 1]). `next_pair` then moves on to fresh outputs.
+///
+/// Each call is counted as one draw from the wrapped generator, which
+/// holds for the crate's generators except `fill_bytes`. When the passes
+/// make different numbers of calls, the next pair starts after the longer
+/// one, so pairs never share outputs.
+///
+/// Only samplers that are a monotone function of each draw give
+/// antithetic pairs: inverse-transform sampling from `next_f64` or
+/// `next_f32`, such as `-u.ln()` for exponential variates, and bounded
+/// integers from `gen_below` up to the rare rejected draw. The ziggurat
+/// samplers `StandardNormal` and `Exp1`, and the rejection samplers built
+/// on them, pick a layer and a sign from separate bits of each word, so
+/// inverting the word does not turn `z` into `-z` or mirror the
+/// distribution.
+///
+/// ```rust
+/// use xorshift::{Antithetic, Rng, Xoroshiro128};
+///
+/// let mut rng = Antithetic::new(Xoroshiro128::from_seed_conditioned(&[42]));
+/// let u = rng.next_f64();
+/// rng.antithetic();
+/// assert_eq!(rng.next_f64(), 1.0 - u);
+/// rng.next_pair();
+/// ```
+#[derive(Clone, Debug)]
+pub struct Antithetic<R> {
+    start: R,
+    rng: R,
+    calls: u64,
+    first_pass: Option<(R, u64)>,
+}
+
+impl<R: Rng + Clone> Antithetic<R> {
+    /// Wrap `rng`, starting the first pass of a pair.
+    pub fn new(rng: R) -> Antithetic<R> {
+        Antithetic {
+            start: rng.clone(),
+            rng,
+            calls: 0,
+            first_pass: None,
+        }
+    }
+
+    /// Whether outputs are currently inverted.
+    pub fn is_antithetic(&self) -> bool {
+        self.first_pass.is_some()
+    }
+
+    /// Rewind to the start of the current pair and invert further outputs.
+    ///
+    /// Calling this during the second pass rewinds it again.
+    pub fn antithetic(&mut self) {
+        if self.first_pass.is_none() {
+            self.first_pass = Some((self.rng.clone(), self.calls));
+        }
+        self.rng = self.start.clone();
+        self.calls = 0;
+    }
+
+    /// Start a new pair, continuing after the longer of the two passes.
+    pub fn next_pair(&mut self) {
+        if let Some((end, calls)) = self.first_pass.take() {
+            if calls >= self.calls {
+                self.rng = end;
+            }
+        }
+        self.start = self.rng.clone();
+        self.calls = 0;
+    }
+
+    /// Return the wrapped generator, positioned after the current pass.
+    pub fn into_inner(self) -> R {
+        self.rng
+    }
+}
+
+impl<R: Rng + Clone> Rng for Antithetic<R> {
+    #[inline]
+    fn next_u32(&mut self) -> u32 {
+        self.calls += 1;
+        let x = self.rng.next_u32();
+        if self.is_antithetic() { !x } else { x }
+    }
+
+    #[inline]
+    fn next_u64(&mut self) -> u64 {
+        self.calls += 1;
+        let x = self.rng.next_u64();
+        if self.is_antithetic() { !x } else { x }
+    }
+
+    #[inline]
+    fn next_f32(&mut self) -> f32 {
+        self.calls += 1;
+        let u = self.rng.next_f32();
+        if self.is_antithetic() { 1.0 - u } else { u }
+    }
+
+    #[inline]
+    fn next_f64(&mut self) -> f64 {
+        self.calls += 1;
+        let u = self.rng.next_f64();
+        if self.is_antithetic() { 1.0 - u } else { u }
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.calls += dest.len().div_ceil(8) as u64;
+        self.rng.fill_bytes(dest);
+        if self.is_antithetic() {
+            for b in dest {
+                *b = !*b;
+            }
+        }
+    }
+}
+
+/// Named sub-streams for common random numbers across scenarios.
+///
+/// Each name is given an explicit index, and the name with index `k` gets
+/// the base generator jumped `k + 1` times. A name's stream therefore
+/// depends only on its index, so adding names or listing them in another
+/// order leaves existing streams unchanged. `stream` always returns a copy
+/// positioned at the start of the sub-stream, so every scenario sees the
+/// same random numbers for the same variable.
+///
+/// ```rust
+/// use xorshift::{CommonStreams, Rng, Xoroshiro128};
+///
+/// let base = Xoroshiro128::from_seed_conditioned(&[42]);
+/// let streams = CommonStreams::new(base, &[("volatility", 0), ("rate", 1)]);
+/// let base_case = streams.stream("volatility").next_f64();
+/// let stressed = streams.stream("volatility").next_f64();
+/// assert_eq!(base_case, stressed);
+/// ```
+#[derive(Clone, Debug)]
+pub struct CommonStreams<R> {
+    streams: HashMap<String, R>,
+}
+
+impl<R: RngJump + Clone> CommonStreams<R> {
+    /// Create sub-streams of `base` for `(name, index)` pairs.
+    ///
+    /// Panics if a name or an index is repeated.
+    pub fn new(base: R, names: &[(&str, usize)]) -> CommonStreams<R> {
+        let mut streams = HashMap::with_capacity(names.len());
+        for (i, &(name, index)) in names.iter().enumerate() {
+            assert!(names[..i].iter().all(|&(_, other)| other != index),
+                    "CommonStreams::new called with a repeated index.");
+            let mut rng = base.clone();
+            rng.jump(index + 1);
+            let previous = streams.insert(name.to_string(), rng);
+            assert!(previous.is_none(), "CommonStreams::new called with a repeated name.");
+        }
+        CommonStreams { streams }
+    }
+
+    /// Return a generator at the start of the sub-stream for `name`.
+    ///
+    /// Panics if `name` was not registered.
+    pub fn stream(&self, name: &str) -> R {
+        match self.streams.get(name) {
+            Some(rng) => rng.clone(),
+            None => panic!("CommonStreams::stream called with unregistered name {:?}.", name),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::Rng;
+    use xoroshiro128::Xoroshiro128;
+    use RngJump;
+    use super::{Antithetic, CommonStreams};
+    #[test]
+    fn test_antithetic() {
+        let base = Xoroshiro128::from_seed_conditioned(&[1, 2]);
+        let mut rng = Antithetic::new(base);
+        let first = (rng.next_u64(), rng.next_u32(), rng.next_f64(), rng.next_f32());
+        rng.antithetic();
+        assert!(rng.is_antithetic());
+        let second = (rng.next_u64(), rng.next_u32(), rng.next_f64(), rng.next_f32());
+        assert_eq!(second, (!first.0, !first.1, 1.0 - first.2, 1.0 - first.3));
+
+        // Rewinding again replays the same inverted outputs
+        rng.antithetic();
+        assert_eq!(rng.next_u64(), !first.0);
+
+        // The next pair continues after the first pass
+        let mut expected = base;
+        (0..4).for_each(|_| { expected.next_u64(); });
+        rng.next_pair();
+        assert!(!rng.is_antithetic());
+        assert_eq!(rng.next_u64(), expected.next_u64());
+
+        // ... or after the second pass, if that was longer
+        rng.antithetic();
+        (0..3).for_each(|_| { rng.next_u64(); });
+        expected.next_u64();
+        expected.next_u64();
+        rng.next_pair();
+        let x = rng.next_u64();
+        assert_eq!(x, expected.next_u64());
+
+        let mut bytes = [0; 12];
+        rng.antithetic();
+        rng.fill_bytes(&mut bytes);
+        assert_eq!(bytes[..8], (!x).to_le_bytes());
+    }
+
+    #[test]
+    fn test_variance() {
+        // Averaging each pair of a monotone estimator cuts its variance well
+        // below that of independent draws
+        let mut rng = Antithetic::new(Xoroshiro128::from_seed_conditioned(&[1, 2]));
+        let (mut sum, mut sum_sq) = (0.0, 0.0);
+        for _ in 0..10000 {
+            let a = rng.next_f64().sqrt();
+            rng.antithetic();
+            let b = rng.next_f64().sqrt();
+            rng.next_pair();
+            let x = (a + b) / 2.0;
+            sum += x;
+            sum_sq += x * x;
+        }
+        let mean = sum / 10000.0;
+        let var = sum_sq / 10000.0 - mean * mean;
+        assert!((mean - 2.0 / 3.0).abs() < 0.002);
+        assert!(var < 0.1 * (1.0 / 18.0));
+    }
+
+    #[test]
+    fn test_common_streams() {
+        let base = Xoroshiro128::from_seed_conditioned(&[1, 2]);
+        let streams = CommonStreams::new(base, &[("vol", 0), ("rate", 1)]);
+        let mut vol = streams.stream("vol");
+        let mut rate = streams.stream("rate");
+        assert_eq!(streams.stream("vol").next_u64(), vol.next_u64());
+        assert_eq!(streams.stream("rate").next_u64(), rate.next_u64());
+
+        let mut expected = base;
+        expected.jump(2);
+        assert_eq!(streams.stream("rate").next_u64(), expected.next_u64());
+
+        // Listing the names in another order, or adding one, keeps the
+        // existing streams
+        let reordered = CommonStreams::new(base, &[("div", 2), ("rate", 1), ("vol", 0)]);
+        assert_eq!(reordered.stream("rate").next_u64(), streams.stream("rate").next_u64());
+        assert_eq!(reordered.stream("vol").next_u64(), streams.stream("vol").next_u64());
+        assert!(reordered.stream("div").next_u64() != streams.stream("rate").next_u64());
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_repeated_index() {
+        let base = Xoroshiro128::from_seed_conditioned(&[1, 2]);
+        CommonStreams::new(base, &[("vol", 0), ("rate", 0)]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_unregistered_stream() {
+        let base = Xoroshiro128::from_seed_conditioned(&[1, 2]);
+        CommonStreams::new(base, &[("vol", 0)]).stream("rate");
+    }
+}
@@ -8,11 +8,14 @@
 
 //! The Xoroshiro128+ random number generator.
 
-use std::num::Wrapping as w;
-
+#[cfg(feature = "std")]
 use rand::{Rand, Rng, SeedableRng};
 
-use RngJump;
+use {RngInfo, RngJump};
+#[cfg(feature = "getrandom")]
+use entropy::{self, EntropyError};
+use float;
+use seed;
 
 const STATE_SIZE: usize = 2;
 
@@ -50,38 +53,149 @@ const STATE_SIZE: usize = 2;
 #[derive(Copy, Clone)]
 pub struct Xoroshiro128([u64; 2]);
 
+#[cfg(feature = "std")]
 static EMPTY: Xoroshiro128 = Xoroshiro128([0, 0]);
-static JUMP: [u64; 2] = [0xbeac0467eba5facb, 0xd86b048b86aa9922];
+const JUMP: [u64; 2] = [0xbeac0467eba5facb, 0xd86b048b86aa9922];
 
 #[inline]
-fn rotl(x: u64, k: i32) -> u64 {
-    (x << k) | (x >> (64 - k))
+const fn rotl(x: u64, k: u32) -> u64 {
+    x.rotate_left(k)
 }
 
+impl Xoroshiro128 {
+    /// Create a generator from its raw state.
+    ///
+    /// The state must not be everywhere zero.
+    pub const fn new(state: [u64; STATE_SIZE]) -> Xoroshiro128 {
+        Xoroshiro128(state)
+    }
+
+    /// Create a generator from seed material of any non-zero length.
+    ///
+    /// The seed words are passed through a splitmix64 generator, so that
+    /// low-entropy seeds such as repeated words still produce a well mixed
+    /// initial state.
+    pub fn from_seed_conditioned(seed: &[u64]) -> Xoroshiro128 {
+        let mut state = [0; STATE_SIZE];
+        seed::condition("Xoroshiro128", seed, &mut state);
+        Xoroshiro128(state)
+    }
+
+    /// Create a generator for the stream named `label` under `master_seed`.
+    ///
+    /// Each label gives an independent stream that does not depend on the
+    /// order in which streams are created. The derivation is stable across
+    /// crate versions and platforms.
+    pub fn from_label(master_seed: u64, label: &str) -> Xoroshiro128 {
+        let mut state = [0; STATE_SIZE];
+        seed::label(master_seed, label, &mut state);
+        Xoroshiro128(state)
+    }
+
+    /// Create a generator seeded from operating system entropy.
+    #[cfg(feature = "getrandom")]
+    pub fn from_entropy() -> Result<Xoroshiro128, EntropyError> {
+        let mut state = [0; STATE_SIZE];
+        entropy::fill(&mut state)?;
+        Ok(Xoroshiro128(state))
+    }
+
+    /// Return the next random `u32`, taken from the upper half of the next
+    /// `u64` to avoid the weaker low bits.
+    #[inline]
+    pub const fn next_u32(&mut self) -> u32 {
+        (self.next_u64() >> 32) as u32
+    }
+
+    /// Return the next random `u64`.
+    #[inline]
+    pub const fn next_u64(&mut self) -> u64 {
+        let s0 = self.0[0];
+        let mut s1 = self.0[1];
+        let result = s0.wrapping_add(s1);
+
+        s1 ^= s0;
+        self.0[0] = rotl(s0, 55) ^ s1 ^ (s1 << 14);
+        self.0[1] = rotl(s1, 36);
+
+        result
+    }
+
+    /// Return the next random `f64` in [0, 1), built from the upper 53 bits
+    /// of the next `u64`.
+    #[inline]
+    pub fn next_f64(&mut self) -> f64 {
+        float::f64_from_u64(self.next_u64())
+    }
+
+    /// Return the next random `f32` in [0, 1), built from the upper 24 bits
+    /// of the next `u64`.
+    #[inline]
+    pub fn next_f32(&mut self) -> f32 {
+        float::f32_from_u64(self.next_u64())
+    }
+
+    // The raw state, used to build the multi-lane generators.
+    #[inline]
+    pub(crate) const fn state(&self) -> [u64; STATE_SIZE] {
+        self.0
+    }
+
+    /// Return the generator advanced by a single jump.
+    ///
+    /// Equivalent to `RngJump::jump(1)`, but usable in constant
+    /// expressions.
+    pub const fn jumped(mut self) -> Xoroshiro128 {
+        let mut s0: u64 = 0;
+        let mut s1: u64 = 0;
+
+        let mut i = 0;
+        while i < JUMP.len() {
+            let mut b = 0;
+            while b < 64 {
+                if (JUMP[i] & 1 << b) != 0 {
+                    s0 ^= self.0[0];
+                    s1 ^= self.0[1];
+                }
+                self.next_u64();
+                b += 1;
+            }
+            i += 1;
+        }
+        Xoroshiro128([s0, s1])
+    }
+}
+
+#[cfg(feature = "std")]
 impl Rng for Xoroshiro128 {
+    #[inline]
     fn next_u32(&mut self) -> u32 {
-        self.next_u64() as u32
+        Xoroshiro128::next_u32(self)
     }
 
     #[inline]
     fn next_u64(&mut self) -> u64 {
-        let s0 = w(self.0[0]);
-        let mut s1 = w(self.0[1]);
-        let result = s0 + s1;
+        Xoroshiro128::next_u64(self)
+    }
 
-        s1 ^= s0;
-        self.0[0] = (w(rotl(s0.0, 55)) ^ s1 ^ (s1 << 14)).0;
-        self.0[1] = rotl(s1.0, 36);
+    #[inline]
+    fn next_f32(&mut self) -> f32 {
+        Xoroshiro128::next_f32(self)
+    }
 
-        result.0
+    #[inline]
+    fn next_f64(&mut self) -> f64 {
+        Xoroshiro128::next_f64(self)
     }
 }
 
+#[cfg(feature = "std")]
 impl<'a> SeedableRng<&'a [u64]> for Xoroshiro128 {
     fn reseed(&mut self, seed: &'a [u64]) {
         if seed.len() < 2 {
             panic!("Xoroshiro128 seed needs at least two u64s for seeding.");
         }
+        seed::check("Xoroshiro128", &seed[..STATE_SIZE]);
         self.0[0] = seed[0];
         self.0[1] = seed[1];
     }
@@ -93,6 +207,7 @@ impl<'a> SeedableRng<&'a [u64]> for Xoroshiro128 {
     }
 }
 
+#[cfg(feature = "std")]
 impl Rand for Xoroshiro128 {
     fn rand<R: Rng>(other: &mut R) -> Xoroshiro128 {
         let mut key: [u64; STATE_SIZE] = [0; STATE_SIZE];
@@ -106,24 +221,17 @@ impl Rand for Xoroshiro128 {
 impl RngJump for Xoroshiro128 {
     fn jump(&mut self, count: usize) {
         for _ in 0..count {
-            let mut s0: u64 = 0;
-            let mut s1: u64 = 0;
-
-            for i in &JUMP {
-                for b in 0..64 {
-                    if (i & 1 << b) != 0 {
-                        s0 ^= self.0[0];
-                        s1 ^= self.0[1];
-                    }
-                    self.next_u64();
-                }
-            }
-            self.0[0] = s0;
-            self.0[1] = s1;
+            *self = self.jumped();
         }
     }
 }
 
+impl RngInfo for Xoroshiro128 {
+    const STATE_BITS: u32 = 128;
+    const PERIOD_LOG2: u32 = 128;
+    const JUMP_LOG2: Option<u32> = Some(64);
+    const WEAK_LOW_BITS: u32 = 1;
+}
 
 #[cfg(test)]
 mod tests {
@@ -0,0 +1,116 @@
+// Written by Alexander Stocko <as@coder.gg>
+//
+// To the extent possible under law, the author has dedicated all copyright
+// and related and neighboring rights to this software to the public domain
+// worldwide. This software is distributed without any warranty.
+//
+// See <LICENSE or http://creativecommons.org/publicdomain/zero/1.0/>
+
+//! Multi-lane Xoroshiro128+ random number generators.
+
+#[cfg(all(feature = "std", target_arch = "x86_64"))]
+use std::arch::x86_64::*;
+
+use lanes::Kernel;
+use xoroshiro128::Xoroshiro128;
+
+#[cfg(all(feature = "std", target_arch = "x86_64"))]
+macro_rules! rotl256 {
+    ($x:expr, $k:expr) => {
+        _mm256_or_si256(_mm256_slli_epi64::<$k>($x), _mm256_srli_epi64::<{ 64 - $k }>($x))
+    }
+}
+
+/// The xoroshiro128+ step.
+struct Step;
+
+impl Kernel for Step {
+    #[inline(always)]
+    fn step(s0: &mut u64, s1: &mut u64) -> u64 {
+        let a = *s0;
+        let b = *s1 ^ a;
+        let result = a.wrapping_add(*s1);
+        *s0 = a.rotate_left(55) ^ b ^ (b << 14);
+        *s1 = b.rotate_left(36);
+        result
+    }
+
+    #[cfg(all(feature = "std", target_arch = "x86_64"))]
+    #[target_feature(enable = "avx2")]
+    unsafe fn fill_avx2(s0: &mut [u64; 4], s1: &mut [u64; 4], dest: &mut [u64]) {
+        let mut a = _mm256_loadu_si256(s0.as_ptr() as *const __m256i);
+        let mut b = _mm256_loadu_si256(s1.as_ptr() as *const __m256i);
+
+        for block in dest.chunks_exact_mut(4) {
+            let result = _mm256_add_epi64(a, b);
+            _mm256_storeu_si256(block.as_mut_ptr() as *mut __m256i, result);
+
+            b = _mm256_xor_si256(b, a);
+            a = _mm256_xor_si256(_mm256_xor_si256(rotl256!(a, 55), b),
+                                 _mm256_slli_epi64::<14>(b));
+            b = rotl256!(b, 36);
+        }
+
+        _mm256_storeu_si256(s0.as_mut_ptr() as *mut __m256i, a);
+        _mm256_storeu_si256(s1.as_mut_ptr() as *mut __m256i, b);
+    }
+
+    // The AVX-512 intrinsics need Rust 1.89, so this is behind the opt-in
+    // `avx512` feature.
+    #[cfg(all(feature = "std", feature = "avx512", target_arch = "x86_64"))]
+    #[allow(clippy::incompatible_msrv)]
+    #[target_feature(enable = "avx512f")]
+    unsafe fn fill_avx512(s0: &mut [u64; 8], s1: &mut [u64; 8], dest: &mut [u64]) {
+        let mut a = _mm512_loadu_si512(s0.as_ptr() as *const __m512i);
+        let mut b = _mm512_loadu_si512(s1.as_ptr() as *const __m512i);
+
+        for block in dest.chunks_exact_mut(8) {
+            let result = _mm512_add_epi64(a, b);
+            _mm512_storeu_si512(block.as_mut_ptr() as *mut __m512i, result);
+
+            b = _mm512_xor_si512(b, a);
+            a = _mm512_xor_si512(_mm512_xor_si512(_mm512_rol_epi64::<55>(a), b),
+                                 _mm512_slli_epi64::<14>(b));
+            b = _mm512_rol_epi64::<36>(b);
+        }
+
+        _mm512_storeu_si512(s0.as_mut_ptr() as *mut __m512i, a);
+        _mm512_storeu_si512(s1.as_mut_ptr() as *mut __m512i, b);
+    }
+}
+
+multi_lane! {
+    /// Four independent xoroshiro128+ streams stepped together.
+    ///
+    /// # Description
+    /// Lane `i` starts from the base generator advanced by `i` jumps, so each
+    /// lane produces exactly the subsequence of a scalar `Xoroshiro128` after
+    /// `i` calls to `RngJump::jump`. Outputs are interleaved: lane 0, lane 1,
+    /// lane 2, lane 3, then lane 0 again.
+    ///
+    /// Bulk generation uses AVX2 when it is detected at runtime, and a
+    /// portable scalar loop otherwise.
+    pub struct Xoroshiro128x4(Xoroshiro128, Step, 4, fill_x4);
+}
+
+multi_lane! {
+    /// Eight independent xoroshiro128+ streams stepped together.
+    ///
+    /// # Description
+    /// Lane `i` starts from the base generator advanced by `i` jumps, so each
+    /// lane produces exactly the subsequence of a scalar `Xoroshiro128` after
+    /// `i` calls to `RngJump::jump`. Outputs are interleaved in lane order.
+    ///
+    /// With the `avx512` feature, bulk generation uses AVX-512 when it is
+    /// detected at runtime. Otherwise it uses a portable scalar loop.
+    pub struct Xoroshiro128x8(Xoroshiro128, Step, 8, fill_x8);
+}
+
+
+#[cfg(test)]
+mod tests {
+    use xoroshiro128::Xoroshiro128;
+    use super::{Step, Xoroshiro128x4, Xoroshiro128x8};
+
+    test_multi_lane!(Xoroshiro128, Step, Xoroshiro128x4, Xoroshiro128x8);
+}
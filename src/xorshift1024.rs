@@ -8,13 +8,19 @@
 
 //! The Xorshift1024* random number generator.
 
-#![cfg_attr(feature = "cargo-clippy", allow(needless_range_loop))]
+#![allow(clippy::needless_range_loop)]
 
 use std::num::Wrapping as w;
 
+#[cfg(feature = "std")]
 use rand::{Rand, Rng, SeedableRng};
 
-use RngJump;
+use {RngInfo, RngJump};
+#[cfg(feature = "getrandom")]
+use entropy::{self, EntropyError};
+use fill;
+use float;
+use seed;
 
 const STATE_SIZE: usize = 16;
 
@@ -47,6 +53,7 @@ pub struct Xorshift1024 {
     p: usize,
 }
 
+#[cfg(feature = "std")]
 static EMPTY: Xorshift1024 = Xorshift1024 {
     state: [0; 16],
     p: 0,
@@ -70,14 +77,54 @@ static JUMP: [u64; 16] = [0x84242f96eca9c41d,
 
 
 
-impl Rng for Xorshift1024 {
+impl Xorshift1024 {
+    /// Create a generator from its raw state.
+    ///
+    /// The state must not be everywhere zero.
+    pub const fn new(state: [u64; STATE_SIZE]) -> Xorshift1024 {
+        Xorshift1024 { state, p: 0 }
+    }
+
+    /// Create a generator from seed material of any non-zero length.
+    ///
+    /// The seed words are passed through a splitmix64 generator, so that
+    /// low-entropy seeds such as repeated words still produce a well mixed
+    /// initial state.
+    pub fn from_seed_conditioned(seed: &[u64]) -> Xorshift1024 {
+        let mut state = [0; STATE_SIZE];
+        seed::condition("Xorshift1024", seed, &mut state);
+        Xorshift1024 { state, p: 0 }
+    }
+
+    /// Create a generator for the stream named `label` under `master_seed`.
+    ///
+    /// Each label gives an independent stream that does not depend on the
+    /// order in which streams are created. The derivation is stable across
+    /// crate versions and platforms.
+    pub fn from_label(master_seed: u64, label: &str) -> Xorshift1024 {
+        let mut state = [0; STATE_SIZE];
+        seed::label(master_seed, label, &mut state);
+        Xorshift1024 { state, p: 0 }
+    }
+
+    /// Create a generator seeded from operating system entropy.
+    #[cfg(feature = "getrandom")]
+    pub fn from_entropy() -> Result<Xorshift1024, EntropyError> {
+        let mut state = [0; STATE_SIZE];
+        entropy::fill(&mut state)?;
+        Ok(Xorshift1024 { state, p: 0 })
+    }
+
+    /// Return the next random `u32`, taken from the upper half of the next
+    /// `u64` to avoid the weaker low bits.
     #[inline]
-    fn next_u32(&mut self) -> u32 {
-        self.next_u64() as u32
+    pub fn next_u32(&mut self) -> u32 {
+        (self.next_u64() >> 32) as u32
     }
 
+    /// Return the next random `u64`.
     #[inline]
-    fn next_u64(&mut self) -> u64 {
+    pub fn next_u64(&mut self) -> u64 {
         let s0 = self.state[self.p];
         self.p = (self.p + 1) & 15;
         let mut s1 = self.state[self.p];
@@ -87,13 +134,90 @@ impl Rng for Xorshift1024 {
 
         (w(self.state[self.p]) * w(1181783497276652981_u64)).0
     }
+
+    /// Return the next random `f64` in [0, 1), built from the upper 53 bits
+    /// of the next `u64`.
+    #[inline]
+    pub fn next_f64(&mut self) -> f64 {
+        float::f64_from_u64(self.next_u64())
+    }
+
+    /// Return the next random `f32` in [0, 1), built from the upper 24 bits
+    /// of the next `u64`.
+    #[inline]
+    pub fn next_f32(&mut self) -> f32 {
+        float::f32_from_u64(self.next_u64())
+    }
+
+    /// Fill `dest` with random `u64`s.
+    ///
+    /// Equivalent to calling `next_u64` for each element.
+    pub fn fill_u64(&mut self, dest: &mut [u64]) {
+        let mut rng = *self;
+        fill::fill_unrolled(dest, || rng.next_u64());
+        *self = rng;
+    }
+
+    /// Fill `dest` with random `u32`s.
+    ///
+    /// Equivalent to calling `next_u32` for each element.
+    pub fn fill_u32(&mut self, dest: &mut [u32]) {
+        let mut rng = *self;
+        fill::fill_unrolled(dest, || rng.next_u32());
+        *self = rng;
+    }
+
+    /// Fill `dest` with random `f64`s in [0, 1), built from the upper 53
+    /// bits of each `u64`.
+    pub fn fill_f64(&mut self, dest: &mut [f64]) {
+        let mut rng = *self;
+        fill::fill_unrolled(dest, || float::f64_from_u64(rng.next_u64()));
+        *self = rng;
+    }
+
+    /// Fill `dest` with random bytes, taken little-endian from successive
+    /// `u64`s.
+    pub fn fill_bytes(&mut self, dest: &mut [u8]) {
+        let mut rng = *self;
+        fill::fill_bytes_unrolled(dest, || rng.next_u64());
+        *self = rng;
+    }
 }
 
+#[cfg(feature = "std")]
+impl Rng for Xorshift1024 {
+    #[inline]
+    fn next_u32(&mut self) -> u32 {
+        Xorshift1024::next_u32(self)
+    }
+
+    #[inline]
+    fn next_u64(&mut self) -> u64 {
+        Xorshift1024::next_u64(self)
+    }
+
+    #[inline]
+    fn next_f32(&mut self) -> f32 {
+        Xorshift1024::next_f32(self)
+    }
+
+    #[inline]
+    fn next_f64(&mut self) -> f64 {
+        Xorshift1024::next_f64(self)
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        Xorshift1024::fill_bytes(self, dest)
+    }
+}
+
+#[cfg(feature = "std")]
 impl<'a> SeedableRng<&'a [u64]> for Xorshift1024 {
     fn reseed(&mut self, seed: &'a [u64]) {
         if seed.len() < 16 {
             panic!("Xorshift1024 seed needs at least 16 u64s for seeding.");
         }
+        seed::check("Xorshift1024", &seed[..STATE_SIZE]);
 
         for (index, element) in seed.iter().enumerate() {
             self.state[index] = *element;
@@ -107,6 +231,7 @@ impl<'a> SeedableRng<&'a [u64]> for Xorshift1024 {
     }
 }
 
+#[cfg(feature = "std")]
 impl Rand for Xorshift1024 {
     fn rand<R: Rng>(other: &mut R) -> Xorshift1024 {
         let mut key: [u64; STATE_SIZE] = [0; STATE_SIZE];
@@ -139,10 +264,175 @@ impl RngJump for Xorshift1024 {
     }
 }
 
+impl RngInfo for Xorshift1024 {
+    const STATE_BITS: u32 = 1024;
+    const PERIOD_LOG2: u32 = 1024;
+    const JUMP_LOG2: Option<u32> = Some(512);
+    const WEAK_LOW_BITS: u32 = 3;
+}
+
+/// A block-based xorshift1024* generator.
+///
+/// # Description
+/// Produces exactly the same stream as the `Xorshift1024` it was created
+/// from, but regenerates all 16 state words at once instead of stepping a
+/// ring index per output. The shifts on the old words and the final
+/// multiplication are independent across the block and vectorize, leaving
+/// only a short serial xor-shift chain.
+///
+/// Outputs are served from a 16 word buffer, and `fill_u64` writes whole
+/// blocks straight into the destination, which makes this mode well
+/// suited to bulk consumers.
+#[derive(Clone, Copy)]
+pub struct Xorshift1024Block {
+    // Rotated so that the ring index is always zero between blocks.
+    state: [u64; 16],
+    buf: [u64; 16],
+    pos: usize,
+}
+
+// Advance the state by 16 steps, writing the outputs in stream order.
+#[inline(always)]
+fn regenerate(state: &mut [u64; 16], out: &mut [u64]) {
+    let mut t = [0; 16];
+    for j in 0..16 {
+        let s1 = state[j] ^ (state[j] << 31);
+        t[j] = s1 ^ (s1 >> 11);
+    }
+
+    let mut s0 = state[0];
+    for j in 1..17 {
+        s0 = t[j & 15] ^ s0 ^ (s0 >> 30);
+        state[j & 15] = s0;
+    }
+
+    for j in 0..16 {
+        out[j] = state[(j + 1) & 15].wrapping_mul(1181783497276652981_u64);
+    }
+}
+
+impl Xorshift1024Block {
+    /// Create a block generator continuing the stream of `rng`.
+    pub fn new(rng: Xorshift1024) -> Xorshift1024Block {
+        let mut state = [0; 16];
+        for j in 0..16 {
+            state[j] = rng.state[(j + rng.p) & 15];
+        }
+        Xorshift1024Block {
+            state,
+            buf: [0; 16],
+            pos: 16,
+        }
+    }
+
+    /// Return the next random `u32`, taken from the upper half of the next
+    /// `u64` to avoid the weaker low bits.
+    #[inline]
+    pub fn next_u32(&mut self) -> u32 {
+        (self.next_u64() >> 32) as u32
+    }
+
+    /// Return the next random `u64`.
+    #[inline]
+    pub fn next_u64(&mut self) -> u64 {
+        if self.pos == 16 {
+            regenerate(&mut self.state, &mut self.buf);
+            self.pos = 0;
+        }
+        self.pos += 1;
+        self.buf[self.pos - 1]
+    }
+
+    /// Return the next random `f64` in [0, 1), built from the upper 53 bits
+    /// of the next `u64`.
+    #[inline]
+    pub fn next_f64(&mut self) -> f64 {
+        float::f64_from_u64(self.next_u64())
+    }
+
+    /// Return the next random `f32` in [0, 1), built from the upper 24 bits
+    /// of the next `u64`.
+    #[inline]
+    pub fn next_f32(&mut self) -> f32 {
+        float::f32_from_u64(self.next_u64())
+    }
+
+    /// Fill `dest` with random `u64`s.
+    ///
+    /// Equivalent to calling `next_u64` for each element.
+    pub fn fill_u64(&mut self, dest: &mut [u64]) {
+        let buffered = (16 - self.pos).min(dest.len());
+        let (head, rest) = dest.split_at_mut(buffered);
+        head.copy_from_slice(&self.buf[self.pos..self.pos + buffered]);
+        self.pos += buffered;
+
+        let mut blocks = rest.chunks_exact_mut(16);
+        for block in &mut blocks {
+            regenerate(&mut self.state, block);
+        }
+        for x in blocks.into_remainder() {
+            *x = self.next_u64();
+        }
+    }
+
+    /// Fill `dest` with random `u32`s.
+    ///
+    /// Equivalent to calling `next_u32` for each element.
+    pub fn fill_u32(&mut self, dest: &mut [u32]) {
+        fill::fill(dest, || self.next_u32());
+    }
+
+    /// Fill `dest` with random `f64`s in [0, 1), built from the upper 53
+    /// bits of each `u64`.
+    pub fn fill_f64(&mut self, dest: &mut [f64]) {
+        fill::fill(dest, || float::f64_from_u64(self.next_u64()));
+    }
+
+    /// Fill `dest` with random bytes, taken little-endian from successive
+    /// `u64`s.
+    pub fn fill_bytes(&mut self, dest: &mut [u8]) {
+        fill::fill_bytes(dest, || self.next_u64());
+    }
+}
+
+#[cfg(feature = "std")]
+impl Rng for Xorshift1024Block {
+    #[inline]
+    fn next_u32(&mut self) -> u32 {
+        Xorshift1024Block::next_u32(self)
+    }
+
+    #[inline]
+    fn next_u64(&mut self) -> u64 {
+        Xorshift1024Block::next_u64(self)
+    }
+
+    #[inline]
+    fn next_f32(&mut self) -> f32 {
+        Xorshift1024Block::next_f32(self)
+    }
+
+    #[inline]
+    fn next_f64(&mut self) -> f64 {
+        Xorshift1024Block::next_f64(self)
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        Xorshift1024Block::fill_bytes(self, dest)
+    }
+}
+
+impl RngInfo for Xorshift1024Block {
+    const STATE_BITS: u32 = 1024;
+    const PERIOD_LOG2: u32 = 1024;
+    const JUMP_LOG2: Option<u32> = Some(512);
+    const WEAK_LOW_BITS: u32 = 3;
+}
+
 #[cfg(test)]
 mod tests {
     use rand::{Rng, SeedableRng};
-    use super::Xorshift1024;
+    use super::{Xorshift1024, Xorshift1024Block};
     #[test]
     fn test() {
         // Calculated from reference implementation
@@ -204,4 +494,25 @@ mod tests {
         let vals = rng.gen_iter::<u64>().take(t_vals.len()).collect::<Vec<u64>>();
         assert!(::test::iter_eq(t_vals, vals));
     }
+
+    #[test]
+    fn test_block() {
+        let seed: u64 = 1477777179826044140;
+        let mut rng = Xorshift1024::from_seed_conditioned(&[seed]);
+
+        // Start the block generator part way around the ring
+        for _ in 0..5 {
+            rng.next_u64();
+        }
+        let mut block = Xorshift1024Block::new(rng);
+        let vals = rng.gen_iter::<u64>().take(1000).collect::<Vec<u64>>();
+
+        let mut bulk = vec![0; 1000];
+        bulk[0] = block.next_u64();
+        block.fill_u64(&mut bulk[1..20]);
+        block.fill_u64(&mut bulk[20..999]);
+        bulk[999] = block.next_u64();
+        assert_eq!(vals, bulk);
+        assert_eq!(block.next_u64(), rng.next_u64());
+    }
 }
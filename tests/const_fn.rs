@@ -0,0 +1,52 @@
+// Written by Alexander Stocko <as@coder.gg>
+//
+// To the extent possible under law, the author has dedicated all copyright
+// and related and neighboring rights to this software to the public domain
+// worldwide. This software is distributed without any warranty.
+//
+// See <LICENSE or http://creativecommons.org/publicdomain/zero/1.0/>
+
+extern crate xorshift;
+
+use xorshift::*;
+
+const SEED: u64 = 1477776328140003287;
+
+static TABLE: [u64; 4096] = {
+    let mut rng = Xoroshiro128::new([SEED, SEED]).jumped();
+    let mut table = [0; 4096];
+    let mut i = 0;
+    while i < table.len() {
+        table[i] = rng.next_u64();
+        i += 1;
+    }
+    table
+};
+
+const XORSHIFT128: u64 = Xorshift128::new([SEED, SEED]).jumped().next_u64();
+
+const SPLITMIX64: u64 = SplitMix64::new(SEED).advanced(100).next_u64();
+
+#[test]
+fn test_xoroshiro128_const() {
+    let mut rng = Xoroshiro128::new([SEED, SEED]);
+    rng.jump(1);
+    let vals = rng.gen_iter::<u64>().take(TABLE.len()).collect::<Vec<u64>>();
+    assert_eq!(&TABLE[..], &vals[..]);
+}
+
+#[test]
+fn test_xorshift128_const() {
+    let mut rng = Xorshift128::new([SEED, SEED]);
+    rng.jump(1);
+    assert_eq!(XORSHIFT128, rng.next_u64());
+}
+
+#[test]
+fn test_splitmix64_const() {
+    let mut rng = SplitMix64::new(SEED);
+    for _ in 0..100 {
+        rng.next_u64();
+    }
+    assert_eq!(SPLITMIX64, rng.next_u64());
+}
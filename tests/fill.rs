@@ -0,0 +1,65 @@
+// Written by Alexander Stocko <as@coder.gg>
+//
+// To the extent possible under law, the author has dedicated all copyright
+// and related and neighboring rights to this software to the public domain
+// worldwide. This software is distributed without any warranty.
+//
+// See <LICENSE or http://creativecommons.org/publicdomain/zero/1.0/>
+
+extern crate xorshift;
+
+use xorshift::*;
+
+// Compare the bulk fill functions against the per-call path, using lengths
+// that exercise both the unrolled loop and the remainder.
+macro_rules! check_fill {
+    ($rng:expr) => {{
+        let base = $rng;
+        for len in 0..37 {
+            let mut a = base;
+            let mut b = base;
+            let mut vals = vec![0u64; len];
+            a.fill_u64(&mut vals);
+            assert_eq!(vals, b.gen_iter::<u64>().take(len).collect::<Vec<u64>>());
+            assert_eq!(a.next_u64(), b.next_u64());
+
+            let mut a = base;
+            let mut b = base;
+            let mut vals = vec![0u32; len];
+            a.fill_u32(&mut vals);
+            assert_eq!(vals, (0..len).map(|_| b.next_u32()).collect::<Vec<u32>>());
+            assert_eq!(a.next_u64(), b.next_u64());
+
+            let mut a = base;
+            let mut b = base;
+            let mut vals = vec![0f64; len];
+            a.fill_f64(&mut vals);
+            for x in vals {
+                assert!((0.0..1.0).contains(&x));
+                assert_eq!(x, (b.next_u64() >> 11) as f64 / (1u64 << 53) as f64);
+            }
+            assert_eq!(a.next_u64(), b.next_u64());
+
+            // Matches the default byte order of rand's fill_bytes
+            let mut a = base;
+            let mut b = base;
+            let mut vals = vec![0u8; len];
+            a.fill_bytes(&mut vals);
+            let mut expected = Vec::new();
+            while expected.len() < len {
+                let x = b.next_u64();
+                expected.extend((0..8).map(|i| (x >> (8 * i)) as u8));
+            }
+            expected.truncate(len);
+            assert_eq!(vals, expected);
+            assert_eq!(a.next_u64(), b.next_u64());
+        }
+    }}
+}
+
+#[test]
+fn test_fill() {
+    let seed: u64 = 1477780064718404645;
+    check_fill!(Xorshift1024::from_seed_conditioned(&[seed]));
+    check_fill!(Xorshift1024Block::new(Xorshift1024::from_seed_conditioned(&[seed])));
+}
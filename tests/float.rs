@@ -0,0 +1,68 @@
+// Written by Alexander Stocko <as@coder.gg>
+//
+// To the extent possible under law, the author has dedicated all copyright
+// and related and neighboring rights to this software to the public domain
+// worldwide. This software is distributed without any warranty.
+//
+// See <LICENSE or http://creativecommons.org/publicdomain/zero/1.0/>
+
+extern crate xorshift;
+
+use xorshift::*;
+
+#[test]
+fn test_upper_bits() {
+    let seed: u64 = 1477780064718404645;
+    let mut a = Xoroshiro128::from_seed_conditioned(&[seed]);
+    let mut b = a;
+    let mut c = a;
+    for _ in 0..100 {
+        let x = b.next_u64();
+        assert_eq!(a.gen::<f64>(), (x >> 11) as f64 / (1u64 << 53) as f64);
+        let x = b.next_u64();
+        assert_eq!(a.gen::<f32>(), (x >> 40) as f32 / (1u32 << 24) as f32);
+    }
+
+    // The legacy adapter keeps rand's low bit conversion
+    let mut legacy = LegacyU32(c);
+    let x = c.next_u64();
+    assert_eq!(legacy.gen::<f64>(),
+               f64::from_bits(0x3FF0000000000000 | (x & 0xFFFFFFFFFFFFF)) - 1.0);
+}
+
+#[test]
+fn test_ranges() {
+    let mut rng = Xorshift1024::from_seed_conditioned(&[1477777179826044140]);
+    for _ in 0..10000 {
+        let x = rng.next_f64_open();
+        assert!(x > 0.0 && x < 1.0);
+        let x = rng.next_f64_closed();
+        assert!((0.0..=1.0).contains(&x));
+        let x = rng.next_f32_open();
+        assert!(x > 0.0 && x < 1.0);
+        let x = rng.next_f32_closed();
+        assert!((0.0..=1.0).contains(&x));
+    }
+}
+
+#[test]
+fn test_full() {
+    let mut rng = SplitMix64::new(1477776061723855037);
+    let n = 100000;
+    let mut small = 0;
+    let mut fine = 0;
+    for _ in 0..n {
+        let x = rng.next_f64_full();
+        assert!((0.0..1.0).contains(&x));
+        if x < 1.0 / 1024.0 {
+            small += 1;
+            // Below 2^-10 there is precision beyond the 2^-53 grid
+            if (x * (1u64 << 53) as f64).fract() != 0.0 {
+                fine += 1;
+            }
+        }
+        assert!(rng.next_f32_full() < 1.0);
+    }
+    assert!(small > 60 && small < 140);
+    assert!(fine > small / 2);
+}
@@ -393,3 +393,54 @@ fn test_xorshift1024_sm64_seed_jumps() {
     let vals = rng.gen_iter::<u64>().take(t_vals.len()).collect::<Vec<u64>>();
     assert!(iter_eq(t_vals, vals));
 }
+
+#[test]
+fn test_conditioned_seed() {
+    let seed: u64 = 1477777179826044140;
+    let states = [seed; 16];
+
+    let mut rng = Xorshift1024::from_seed_conditioned(&states[..]);
+    let vals = rng.gen_iter::<u64>().take(50).collect::<Vec<u64>>();
+    for (i, x) in vals.iter().enumerate() {
+        assert!(vals[i + 1..].iter().all(|y| x != y));
+    }
+
+    let mut a = Xoroshiro128::from_seed_conditioned(&[seed]);
+    let mut b = Xoroshiro128::from_seed_conditioned(&[seed, 1]);
+    assert!(a.next_u64() != b.next_u64());
+
+    // Trailing zero words still change the state
+    let mut a = Xorshift128::from_seed_conditioned(&[seed]);
+    let mut b = Xorshift128::from_seed_conditioned(&[seed, 0]);
+    assert!(a.next_u64() != b.next_u64());
+    let mut a = Xorshift1024::from_seed_conditioned(&states[..]);
+    let mut b = Xorshift1024::from_seed_conditioned(&[seed; 17]);
+    assert!(a.next_u64() != b.next_u64());
+}
+
+#[test]
+fn test_label_seed() {
+    // Pinned so that label derivation stays stable across releases
+    let seed: u64 = 1477780064718404645;
+
+    let mut rng = Xoroshiro128::from_label(seed, "traffic");
+    assert_eq!(rng.next_u64(), 12265804637720527333);
+    let mut rng = Xoroshiro128::from_label(seed, "weather");
+    assert_eq!(rng.next_u64(), 7881980242662776404);
+    let mut rng = Xorshift1024::from_label(seed, "traffic");
+    assert_eq!(rng.next_u64(), 5406121614569814299);
+    let mut rng = SplitMix64::from_label(seed, "traffic");
+    assert_eq!(rng.next_u64(), 3741050593834521247);
+
+    let mut a = Xorshift128::from_label(seed, "a");
+    let mut b = Xorshift128::from_label(seed, "a\0");
+    assert!(a.next_u64() != b.next_u64());
+
+    // A label never matches the conditioned seed made of the words it packs
+    let mut a = Xoroshiro128::from_label(seed, "a");
+    let mut b = Xoroshiro128::from_seed_conditioned(&[seed, 1, 0x61]);
+    assert!(a.next_u64() != b.next_u64());
+    let mut a = Xorshift1024::from_label(seed, "");
+    let mut b = Xorshift1024::from_seed_conditioned(&[seed, 0]);
+    assert!(a.next_u64() != b.next_u64());
+}